@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::Context7Client;
+    use crate::{Context7Client, Context7Error, RetryPolicy};
     use serde_json::json;
+    use std::time::{Duration, Instant};
     use wiremock::matchers::{header, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -79,11 +80,10 @@ mod tests {
         let client = Context7Client::new_with_base_url(None, mock_server.uri(), false);
         let result = client.search_libraries("test").await;
 
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert!(response.results.is_empty());
-        assert!(response.error.is_some());
-        assert!(response.error.unwrap().contains("Rate limited"));
+        let error = result.unwrap_err();
+        assert!(matches!(error, Context7Error::RateLimited { .. }));
+        assert_eq!(error.code(), "rate_limited");
+        assert!(error.to_string().contains("Rate limited"));
     }
 
     #[tokio::test]
@@ -154,10 +154,9 @@ mod tests {
             .fetch_library_documentation("/nonexistent/library", None, None)
             .await;
 
-        assert!(result.is_ok());
-        let docs = result.unwrap();
-        assert!(docs.is_some());
-        assert!(docs.unwrap().contains("does not exist"));
+        let error = result.unwrap_err();
+        assert!(matches!(error, Context7Error::NotFound));
+        assert!(error.to_string().contains("does not exist"));
     }
 
     #[tokio::test]
@@ -240,4 +239,105 @@ mod tests {
             .await;
         assert!(result.is_ok());
     }
+
+    fn fast_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+            deadline: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context7_client_retries_after_rate_limit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/nixos/nix"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/nixos/nix"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# Nix after retry"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_retry_policy(fast_retry_policy());
+        let docs = client
+            .fetch_library_documentation("/nixos/nix", None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(docs.as_deref(), Some("# Nix after retry"));
+    }
+
+    #[tokio::test]
+    async fn test_context7_client_retries_server_errors_until_exhausted() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_retry_policy(fast_retry_policy());
+        let error = client.search_libraries("nix").await.unwrap_err();
+
+        assert!(matches!(error, Context7Error::HttpStatus { status: 503 }));
+    }
+
+    #[tokio::test]
+    async fn test_context7_client_does_not_retry_client_errors() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/missing/lib"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_retry_policy(fast_retry_policy());
+        let error = client
+            .fetch_library_documentation("/missing/lib", None, None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Context7Error::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_context7_client_retry_after_beyond_deadline_returns_promptly() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "120"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_retry_policy(fast_retry_policy());
+        let started = Instant::now();
+        let error = client.search_libraries("nix").await.unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(
+            error,
+            Context7Error::RateLimited {
+                retry_after: Some(retry_after)
+            } if retry_after == Duration::from_secs(120)
+        ));
+    }
 }
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+
+use crate::client::{Context7Client, SearchResponse};
+use crate::error::Context7Error;
+
+/// A source of library documentation that `Context7Tool` can serve.
+///
+/// `Context7Client` is the default; other implementations (local docs, mirrors,
+/// test doubles) can be injected with `Context7Tool::with_backend`.
+#[async_trait]
+pub trait DocsBackend: Send + Sync {
+    /// Finds libraries matching `query`.
+    async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error>;
+
+    /// Finds libraries matching a partially typed `query` for completion.
+    ///
+    /// Runs on every keystroke, so it should answer quickly or give up rather
+    /// than retry.
+    async fn suggest_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        self.search_libraries(query).await
+    }
+
+    /// Fetches documentation for `library_id`, or `None` when there is none.
+    async fn fetch_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error>;
+
+    /// Fetches documentation again, bypassing any cache; used to detect changes.
+    async fn refresh_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        self.fetch_library_documentation(library_id, tokens, topic)
+            .await
+    }
+
+    /// Libraries whose documentation can be served without fetching it again.
+    fn cached_libraries(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[async_trait]
+impl DocsBackend for Context7Client {
+    async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        Self::search_libraries(self, query).await
+    }
+
+    async fn suggest_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        Self::suggest_libraries(self, query).await
+    }
+
+    async fn fetch_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        Self::fetch_library_documentation(self, library_id, tokens, topic).await
+    }
+
+    async fn refresh_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        Self::refresh_library_documentation(self, library_id, tokens, topic).await
+    }
+
+    fn cached_libraries(&self) -> Vec<String> {
+        Self::cached_libraries(self)
+    }
+}
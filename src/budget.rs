@@ -0,0 +1,66 @@
+use crate::client::MINIMUM_TOKENS;
+
+/// How one library of a batched fetch asks for its part of a token budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenShare {
+    /// An explicit token count, taken off the top of the budget.
+    Fixed(u32),
+    /// A relative weight for sharing whatever the fixed requests leave.
+    Weighted(u32),
+}
+
+/// Divides `total` tokens across a batch, returning each library's tokens in order.
+///
+/// Fixed requests are honoured first, raised to the per-library minimum as every
+/// backend does. Each weighted request then gets the minimum too, and the rest
+/// is shared in proportion to the weights. Fails when the budget cannot cover that.
+pub fn split_token_budget(total: u32, shares: &[TokenShare]) -> Result<Vec<u32>, String> {
+    let fixed: u64 = shares
+        .iter()
+        .map(|share| match share {
+            TokenShare::Fixed(tokens) => u64::from((*tokens).max(MINIMUM_TOKENS)),
+            TokenShare::Weighted(_) => 0,
+        })
+        .sum();
+    let remaining = u64::from(total).checked_sub(fixed).ok_or_else(|| {
+        format!("totalTokens {total} is less than the {fixed} tokens requested per library")
+    })?;
+
+    let weights: Vec<u64> = shares
+        .iter()
+        .filter_map(|share| match share {
+            TokenShare::Weighted(weight) => Some(u64::from((*weight).max(1))),
+            TokenShare::Fixed(_) => None,
+        })
+        .collect();
+    let needed = weights.len() as u64 * u64::from(MINIMUM_TOKENS);
+    let surplus = remaining.checked_sub(needed).ok_or_else(|| {
+        format!(
+            "totalTokens {total} leaves {remaining} tokens for {} libraries without explicit tokens; each needs at least {MINIMUM_TOKENS}",
+            weights.len()
+        )
+    })?;
+
+    // Shares are rounded down; the few tokens this leaves go to the first libraries.
+    let total_weight: u64 = weights.iter().sum();
+    let mut weighted: Vec<u64> = weights
+        .iter()
+        .map(|weight| u64::from(MINIMUM_TOKENS) + surplus * weight / total_weight.max(1))
+        .collect();
+    let handed_out: u64 = weighted.iter().sum::<u64>() - needed;
+    for tokens in weighted.iter_mut().take((surplus - handed_out) as usize) {
+        *tokens += 1;
+    }
+
+    let mut weighted = weighted.into_iter();
+    Ok(shares
+        .iter()
+        .map(|share| match share {
+            TokenShare::Fixed(tokens) => (*tokens).max(MINIMUM_TOKENS),
+            TokenShare::Weighted(_) => weighted
+                .next()
+                .and_then(|tokens| u32::try_from(tokens).ok())
+                .unwrap_or(MINIMUM_TOKENS),
+        })
+        .collect())
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::{TokenShare, split_token_budget};
+
+    #[test]
+    fn test_budget_is_shared_by_weight() {
+        let shares = [
+            TokenShare::Weighted(2),
+            TokenShare::Weighted(1),
+            TokenShare::Weighted(1),
+        ];
+        assert_eq!(
+            split_token_budget(7000, &shares).unwrap(),
+            [3000, 2000, 2000]
+        );
+        let tokens = split_token_budget(10_001, &shares).unwrap();
+        assert_eq!(tokens, [4501, 2750, 2750]);
+        assert_eq!(tokens.iter().sum::<u32>(), 10_001);
+    }
+
+    #[test]
+    fn test_fixed_tokens_come_off_the_top() {
+        let shares = [
+            TokenShare::Fixed(4000),
+            TokenShare::Weighted(1),
+            TokenShare::Fixed(200),
+            TokenShare::Weighted(1),
+        ];
+        // The tiny fixed request is raised to the 1000-token minimum.
+        assert_eq!(
+            split_token_budget(9000, &shares).unwrap(),
+            [4000, 2000, 1000, 2000]
+        );
+    }
+
+    #[test]
+    fn test_budget_too_small_is_rejected() {
+        let error = split_token_budget(2500, &[TokenShare::Weighted(1); 3]).unwrap_err();
+        assert!(error.contains("each needs at least 1000"), "{error}");
+
+        let error = split_token_budget(3000, &[TokenShare::Fixed(5000), TokenShare::Weighted(1)])
+            .unwrap_err();
+        assert!(error.contains("less than the 5000 tokens"), "{error}");
+    }
+}
@@ -0,0 +1,397 @@
+use crate::client::SearchResult;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub dir: PathBuf,
+    pub ttl: Duration,
+    pub max_bytes: u64,
+}
+
+impl CacheConfig {
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            ttl: DEFAULT_TTL,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// `$XDG_CACHE_HOME/c67-mcp`, falling back to `~/.cache/c67-mcp`.
+    #[must_use]
+    pub fn default_dir() -> Option<PathBuf> {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .map(|base| base.join("c67-mcp"))
+    }
+}
+
+/// Identifies one documentation fetch. The API key is only ever stored hashed.
+#[derive(Debug, Clone, Copy)]
+pub struct DocsKey<'a> {
+    pub library_id: &'a str,
+    pub topic: Option<&'a str>,
+    pub tokens: u32,
+    pub api_key: Option<&'a str>,
+}
+
+impl DocsKey<'_> {
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.library_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.topic.unwrap_or_default().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.tokens.to_le_bytes());
+        hasher.update([0]);
+        hasher.update(api_key_identity(self.api_key).as_bytes());
+        to_hex(&hasher.finalize())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchEntry {
+    query: String,
+    /// Hash of the API key the search ran with, empty for none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    api_key: String,
+    fetched_at: u64,
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DocsEntry {
+    library_id: String,
+    topic: Option<String>,
+    tokens: u32,
+    /// Hash of the API key the entry was fetched with, empty for none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    api_key: String,
+    fetched_at: u64,
+    content: String,
+}
+
+/// On-disk documentation cache with a TTL and least-recently-used eviction.
+///
+/// Each entry is a JSON file whose modification time records its last use.
+pub struct DocsCache {
+    config: CacheConfig,
+    lock: Mutex<()>,
+    /// API key hash and library ID of each documentation entry, loaded on first
+    /// use so listing libraries does not parse every entry. Only touched while
+    /// `lock` is held.
+    libraries: Mutex<Option<HashMap<PathBuf, (String, String)>>>,
+}
+
+impl DocsCache {
+    #[must_use]
+    pub const fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            lock: Mutex::new(()),
+            libraries: Mutex::new(None),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    fn docs_dir(&self) -> PathBuf {
+        self.config.dir.join("docs")
+    }
+
+    fn search_dir(&self) -> PathBuf {
+        self.config.dir.join("search")
+    }
+
+    /// Returns cached documentation if present and younger than the TTL.
+    #[must_use]
+    pub fn get(&self, key: &DocsKey<'_>) -> Option<String> {
+        let _guard = self.lock.lock().ok()?;
+        let path = self.docs_dir().join(format!("{}.json", key.digest()));
+        let entry: DocsEntry = read_entry(&path)?;
+
+        if now_secs().saturating_sub(entry.fetched_at) > self.config.ttl.as_secs() {
+            return None;
+        }
+
+        touch(&path);
+        Some(entry.content)
+    }
+
+    /// Stores documentation, evicting least recently used entries beyond the size limit.
+    pub fn put(&self, key: &DocsKey<'_>, content: &str) -> Result<()> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow::anyhow!("cache lock poisoned"))?;
+        let dir = self.docs_dir();
+        create_private_dir(&dir)?;
+
+        let entry = DocsEntry {
+            library_id: key.library_id.to_string(),
+            topic: key.topic.map(ToString::to_string),
+            tokens: key.tokens,
+            api_key: api_key_identity(key.api_key),
+            fetched_at: now_secs(),
+            content: content.to_string(),
+        };
+        let path = dir.join(format!("{}.json", key.digest()));
+        write_private(&path, &serde_json::to_vec(&entry)?)?;
+        if let Ok(mut libraries) = self.libraries.lock()
+            && let Some(libraries) = libraries.as_mut()
+        {
+            libraries.insert(path, (entry.api_key, entry.library_id));
+        }
+
+        self.evict()
+    }
+
+    /// Returns documentation for `library_id` and `topic` regardless of age.
+    ///
+    /// Prefers the exact key, then the entry fetched with the same API key and the
+    /// largest token budget.
+    #[must_use]
+    pub fn find_stale(&self, key: &DocsKey<'_>) -> Option<String> {
+        let _guard = self.lock.lock().ok()?;
+        let exact = self.docs_dir().join(format!("{}.json", key.digest()));
+        if let Some(entry) = read_entry::<DocsEntry>(&exact) {
+            touch(&exact);
+            return Some(entry.content);
+        }
+
+        let api_key = api_key_identity(key.api_key);
+        self.docs_entries()
+            .into_iter()
+            .filter(|(_, entry)| {
+                entry.library_id == key.library_id
+                    && entry.topic.as_deref() == key.topic
+                    && entry.api_key == api_key
+            })
+            .max_by_key(|(_, entry)| entry.tokens)
+            .map(|(path, entry)| {
+                touch(&path);
+                entry.content
+            })
+    }
+
+    /// Topics cached for `library_id` with `api_key`; `None` stands for the
+    /// untopiced documentation.
+    #[must_use]
+    pub fn cached_topics(&self, library_id: &str, api_key: Option<&str>) -> Vec<Option<String>> {
+        let Ok(_guard) = self.lock.lock() else {
+            return Vec::new();
+        };
+        let api_key = api_key_identity(api_key);
+        let mut topics: Vec<Option<String>> = self
+            .docs_entries()
+            .into_iter()
+            .filter(|(_, entry)| entry.library_id == library_id && entry.api_key == api_key)
+            .map(|(_, entry)| entry.topic)
+            .collect();
+        topics.sort();
+        topics.dedup();
+        topics
+    }
+
+    /// Library IDs (without the leading slash) with any documentation cached
+    /// for `api_key`.
+    #[must_use]
+    pub fn cached_libraries(&self, api_key: Option<&str>) -> Vec<String> {
+        let Ok(_guard) = self.lock.lock() else {
+            return Vec::new();
+        };
+        let Ok(mut index) = self.libraries.lock() else {
+            return Vec::new();
+        };
+        let index = index.get_or_insert_with(|| {
+            self.docs_entries()
+                .into_iter()
+                .map(|(path, entry)| (path, (entry.api_key, entry.library_id)))
+                .collect()
+        });
+        let api_key = api_key_identity(api_key);
+        let mut libraries: Vec<String> = index
+            .values()
+            .filter(|(key, _)| *key == api_key)
+            .map(|(_, library_id)| library_id.clone())
+            .collect();
+        libraries.sort();
+        libraries.dedup();
+        libraries
+    }
+
+    /// Remembers the results of a library search for offline use.
+    pub fn put_search(
+        &self,
+        query: &str,
+        api_key: Option<&str>,
+        results: &[SearchResult],
+    ) -> Result<()> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow::anyhow!("cache lock poisoned"))?;
+        let dir = self.search_dir();
+        create_private_dir(&dir)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(query.to_lowercase().as_bytes());
+        hasher.update([0]);
+        hasher.update(api_key_identity(api_key).as_bytes());
+        let path = dir.join(format!("{}.json", to_hex(&hasher.finalize())));
+
+        let entry = SearchEntry {
+            query: query.to_string(),
+            api_key: api_key_identity(api_key),
+            fetched_at: now_secs(),
+            results: results.to_vec(),
+        };
+        write_private(&path, &serde_json::to_vec(&entry)?)?;
+
+        self.evict()
+    }
+
+    /// Every search result seen so far with `api_key`, de-duplicated by library ID.
+    #[must_use]
+    pub fn cached_search_results(&self, api_key: Option<&str>) -> Vec<SearchResult> {
+        let Ok(_guard) = self.lock.lock() else {
+            return Vec::new();
+        };
+        let api_key = api_key_identity(api_key);
+        let mut results: Vec<SearchResult> = read_dir_entries::<SearchEntry>(&self.search_dir())
+            .into_iter()
+            .filter(|(_, entry)| entry.api_key == api_key)
+            .flat_map(|(_, entry)| entry.results)
+            .collect();
+        results.sort_by(|a, b| a.id.cmp(&b.id));
+        results.dedup_by(|a, b| a.id == b.id);
+        results
+    }
+
+    fn docs_entries(&self) -> Vec<(PathBuf, DocsEntry)> {
+        read_dir_entries(&self.docs_dir())
+    }
+
+    /// Removes least recently used documentation and search entries until the
+    /// cache fits in its size limit.
+    fn evict(&self) -> Result<()> {
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = [self.docs_dir(), self.search_dir()]
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+                Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+            })
+            .collect();
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.config.max_bytes {
+            return Ok(());
+        }
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= self.config.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                tracing::debug!("Evicted cache entry {}", path.display());
+                total -= len;
+                if let Ok(mut libraries) = self.libraries.lock()
+                    && let Some(libraries) = libraries.as_mut()
+                {
+                    libraries.remove(&path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_entry<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+fn read_dir_entries<T: for<'de> Deserialize<'de>>(dir: &Path) -> Vec<(PathBuf, T)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                return None;
+            }
+            let value = read_entry(&path)?;
+            Some((path, value))
+        })
+        .collect()
+}
+
+/// Creates `dir` and any missing parents, readable only by the current user.
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Atomically replaces `path` with `contents`, readable only by the current user.
+///
+/// Entries hold documentation fetched with the user's API key, which may cover
+/// private libraries.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::File::options();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&tmp)?.write_all(contents)?;
+    fs::rename(&tmp, path)
+}
+
+fn api_key_identity(api_key: Option<&str>) -> String {
+    api_key.map_or_else(String::new, |key| to_hex(&Sha256::digest(key.as_bytes())))
+}
+
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
@@ -0,0 +1,322 @@
+#[cfg(test)]
+mod tests {
+    use crate::{CacheConfig, Context7Client, Context7Error, DocsCache, DocsKey, SearchResult};
+    use serde_json::json;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn key<'a>(topic: Option<&'a str>, api_key: Option<&'a str>) -> DocsKey<'a> {
+        DocsKey {
+            library_id: "test/lib",
+            topic,
+            tokens: 5000,
+            api_key,
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_keyed_on_topic_and_api_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig::new(dir.path().to_path_buf()));
+
+        cache
+            .put(&key(Some("hooks"), Some("key-a")), "hooks docs")
+            .unwrap();
+
+        assert_eq!(
+            cache.get(&key(Some("hooks"), Some("key-a"))).as_deref(),
+            Some("hooks docs")
+        );
+        assert!(cache.get(&key(Some("routing"), Some("key-a"))).is_none());
+        assert!(cache.get(&key(Some("hooks"), Some("key-b"))).is_none());
+        assert!(cache.get(&key(Some("hooks"), None)).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_entries_are_private_to_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("c67-mcp");
+        let cache = DocsCache::new(CacheConfig::new(root.clone()));
+        cache.put(&key(None, Some("key-a")), "docs").unwrap();
+        cache.put_search("lib", Some("key-a"), &[]).unwrap();
+
+        let mode =
+            |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        for dir in [root.clone(), root.join("docs"), root.join("search")] {
+            assert_eq!(mode(&dir), 0o700, "{}", dir.display());
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    assert_eq!(mode(&path), 0o600, "{}", path.display());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stale_lookup_stays_within_api_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig::new(dir.path().to_path_buf()));
+        cache
+            .put(&key(Some("hooks"), Some("key-a")), "key-a docs")
+            .unwrap();
+
+        let larger = |api_key| DocsKey {
+            tokens: 20000,
+            ..key(Some("hooks"), api_key)
+        };
+        assert_eq!(
+            cache.find_stale(&larger(Some("key-a"))).as_deref(),
+            Some("key-a docs")
+        );
+        assert!(cache.find_stale(&larger(Some("key-b"))).is_none());
+        assert!(cache.find_stale(&larger(None)).is_none());
+    }
+
+    #[test]
+    fn test_cached_listings_stay_within_api_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig::new(dir.path().to_path_buf()));
+        let result = |id: &str| SearchResult {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            total_snippets: None,
+            trust_score: None,
+            versions: None,
+            origin: None,
+        };
+        cache
+            .put(&key(Some("hooks"), Some("key-a")), "key-a docs")
+            .unwrap();
+        cache
+            .put_search("lib", Some("key-a"), &[result("/private/lib")])
+            .unwrap();
+        cache
+            .put_search("lib", Some("key-b"), &[result("/public/lib")])
+            .unwrap();
+
+        assert_eq!(cache.cached_libraries(Some("key-a")), ["test/lib"]);
+        assert!(cache.cached_libraries(Some("key-b")).is_empty());
+        assert!(cache.cached_libraries(None).is_empty());
+        assert_eq!(
+            cache.cached_topics("test/lib", Some("key-a")),
+            [Some("hooks".to_string())]
+        );
+        assert!(cache.cached_topics("test/lib", Some("key-b")).is_empty());
+
+        let ids = |api_key| {
+            cache
+                .cached_search_results(api_key)
+                .into_iter()
+                .map(|result| result.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(Some("key-a")), ["/private/lib"]);
+        assert_eq!(ids(Some("key-b")), ["/public/lib"]);
+        assert!(ids(None).is_empty());
+    }
+
+    #[test]
+    fn test_cache_expires_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig {
+            ttl: Duration::ZERO,
+            ..CacheConfig::new(dir.path().to_path_buf())
+        });
+
+        let key = key(None, None);
+        cache.put(&key, "docs").unwrap();
+        std::thread::sleep(Duration::from_millis(1100));
+
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig {
+            max_bytes: 600,
+            ..CacheConfig::new(dir.path().to_path_buf())
+        });
+        let body = "x".repeat(200);
+
+        cache.put(&key(Some("a"), None), &body).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&key(Some("b"), None), &body).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(cache.get(&key(Some("a"), None)).is_some());
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&key(Some("c"), None), &body).unwrap();
+
+        assert!(cache.get(&key(Some("a"), None)).is_some());
+        assert!(cache.get(&key(Some("b"), None)).is_none());
+        assert!(cache.get(&key(Some("c"), None)).is_some());
+    }
+
+    #[test]
+    fn test_cache_evicts_search_results_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DocsCache::new(CacheConfig {
+            max_bytes: 800,
+            ..CacheConfig::new(dir.path().to_path_buf())
+        });
+        let result = |id: &str| SearchResult {
+            id: id.to_string(),
+            title: "x".repeat(200),
+            description: String::new(),
+            total_snippets: None,
+            trust_score: None,
+            versions: None,
+            origin: None,
+        };
+
+        cache
+            .put_search("old", None, &[result("/old/lib")])
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&key(Some("a"), None), &"x".repeat(200)).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache
+            .put_search("new", None, &[result("/new/lib")])
+            .unwrap();
+
+        let ids: Vec<String> = cache
+            .cached_search_results(None)
+            .into_iter()
+            .map(|result| result.id)
+            .collect();
+        assert_eq!(ids, ["/new/lib"]);
+        assert!(cache.get(&key(Some("a"), None)).is_some());
+    }
+
+    #[test]
+    fn test_cached_libraries_track_puts_and_evictions() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CacheConfig {
+            max_bytes: 600,
+            ..CacheConfig::new(dir.path().to_path_buf())
+        };
+        let library = |library_id| DocsKey {
+            library_id,
+            ..key(None, None)
+        };
+        let body = "x".repeat(200);
+        DocsCache::new(config.clone())
+            .put(&library("/earlier/lib"), &body)
+            .unwrap();
+
+        let cache = DocsCache::new(config);
+        assert_eq!(cache.cached_libraries(None), ["/earlier/lib"]);
+
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&library("/second/lib"), &body).unwrap();
+        assert_eq!(
+            cache.cached_libraries(None),
+            ["/earlier/lib", "/second/lib"]
+        );
+
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(&library("/third/lib"), &body).unwrap();
+        assert_eq!(cache.cached_libraries(None), ["/second/lib", "/third/lib"]);
+    }
+
+    #[tokio::test]
+    async fn test_client_serves_repeated_fetch_from_cache() {
+        let mock_server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/test/cached"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# Cached docs"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_cache(DocsCache::new(CacheConfig::new(dir.path().to_path_buf())));
+
+        for _ in 0..2 {
+            let docs = client
+                .fetch_library_documentation("/test/cached", None, Some("intro"))
+                .await
+                .unwrap();
+            assert_eq!(docs.as_deref(), Some("# Cached docs"));
+        }
+        assert_eq!(client.cached_libraries(), ["/test/cached"]);
+    }
+
+    #[tokio::test]
+    async fn test_offline_client_answers_from_cache_only() {
+        let mock_server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {
+                        "id": "/facebook/react",
+                        "title": "React",
+                        "description": "A JavaScript library for building user interfaces",
+                        "totalSnippets": 100,
+                        "trustScore": 9.0,
+                        "versions": []
+                    }
+                ],
+                "error": null
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/facebook/react"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# React hooks"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let cache = || DocsCache::new(CacheConfig::new(dir.path().to_path_buf()));
+        let online =
+            Context7Client::new_with_base_url(None, mock_server.uri(), false).with_cache(cache());
+        online.search_libraries("react").await.unwrap();
+        online
+            .fetch_library_documentation("/facebook/react", None, Some("hooks"))
+            .await
+            .unwrap();
+
+        let offline = Context7Client::new_with_base_url(None, mock_server.uri(), false)
+            .with_cache(cache())
+            .with_offline(true);
+
+        let search = offline.search_libraries("React").await.unwrap();
+        assert_eq!(search.results.len(), 1);
+        assert_eq!(search.results[0].title, "React");
+
+        let docs = offline
+            .fetch_library_documentation("/facebook/react", Some(20000), Some("hooks"))
+            .await
+            .unwrap();
+        assert_eq!(docs.as_deref(), Some("# React hooks"));
+
+        let missing = offline
+            .fetch_library_documentation("/facebook/react", None, Some("routing"))
+            .await
+            .unwrap_err();
+        assert!(matches!(missing, Context7Error::Offline(_)));
+        let missing = missing.to_string();
+        assert!(missing.contains("not available offline"));
+        assert!(missing.contains("'hooks'"));
+
+        let unknown = offline
+            .fetch_library_documentation("/vercel/next.js", None, None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(unknown.contains("Libraries available offline: /facebook/react"));
+    }
+}
@@ -1,13 +1,26 @@
-use anyhow::Result;
+use crate::cache::{DocsCache, DocsKey};
+use crate::error::Context7Error;
+use crate::retry::{RetryPolicy, is_retryable_error, is_retryable_status, parse_retry_after};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use ureq::tls::{RootCerts, TlsConfig};
-use ureq::{Agent, Error};
+use ureq::{Agent, Body, Error};
 
 const CONTEXT7_API_BASE_URL: &str = "https://context7.com/api";
-const MINIMUM_TOKENS: u32 = 1000;
-const DEFAULT_TOKENS: u32 = 5000;
+pub(crate) const MINIMUM_TOKENS: u32 = 1000;
+pub(crate) const DEFAULT_TOKENS: u32 = 5000;
+/// Largest response body read, matching ureq's own `read_to_string` limit.
+const MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+/// How long a completion search may take; it is never retried.
+const SUGGEST_DEADLINE: Duration = Duration::from_secs(2);
+const OFFLINE_WITHOUT_CACHE: &str =
+    "Offline mode is enabled but no documentation cache is configured.";
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResult {
     pub id: String,
     pub title: String,
@@ -17,6 +30,9 @@ pub struct SearchResult {
     #[serde(rename = "trustScore")]
     pub trust_score: Option<f64>,
     pub versions: Option<Vec<String>>,
+    /// Which configured source returned this result, when several are federated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,10 +41,43 @@ pub struct SearchResponse {
     pub error: Option<String>,
 }
 
+/// A completed exchange; statuses are judged by the caller so retries can see them.
+struct HttpResponse {
+    status: u16,
+    retry_after: Option<Duration>,
+    body: String,
+}
+
+/// Connection pooling and timeouts for the HTTP agent shared by all requests.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub max_idle_connections: usize,
+    pub max_idle_connections_per_host: usize,
+    pub idle_timeout: Duration,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_connections: 10,
+            max_idle_connections_per_host: 4,
+            idle_timeout: Duration::from_secs(60),
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
 pub struct Context7Client {
     api_key: Option<String>,
     base_url: String,
     insecure: bool,
+    agent: Agent,
+    cache: Option<Arc<DocsCache>>,
+    offline: bool,
+    retry: RetryPolicy,
 }
 
 impl Context7Client {
@@ -43,61 +92,104 @@ impl Context7Client {
             api_key,
             base_url,
             insecure,
+            agent: build_agent(insecure, &HttpConfig::default()),
+            cache: None,
+            offline: false,
+            retry: RetryPolicy::default(),
         }
     }
 
-    pub async fn search_libraries(&self, query: &str) -> Result<SearchResponse> {
-        let url = format!("{}/v1/search", self.base_url);
+    /// Rebuilds the shared agent with custom pooling and timeouts.
+    #[must_use]
+    pub fn with_http_config(mut self, config: &HttpConfig) -> Self {
+        self.agent = build_agent(self.insecure, config);
+        self
+    }
 
-        let api_key = self.api_key.clone();
-        let query = query.to_string();
-        let insecure = self.insecure;
-        let result = tokio::task::spawn_blocking(move || {
-            let agent = if insecure {
-                // Create agent that disables certificate verification for corporate MITM environments
-                let tls_config = TlsConfig::builder()
-                    .root_certs(RootCerts::PlatformVerifier) // Use platform's root certs (including corporate)
-                    .disable_verification(true)              // Disable all TLS verification (insecure)
-                    .build();
-
-                Agent::config_builder()
-                    .tls_config(tls_config)
-                    .build()
-                    .new_agent()
-            } else {
-                ureq::agent()
-            };
+    /// Serves repeated documentation fetches from `cache` instead of the network.
+    #[must_use]
+    pub fn with_cache(mut self, cache: DocsCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
 
-            let mut request = agent.get(&url).query("query", &query);
+    /// Answers searches and fetches from the cache only, never touching the network.
+    #[must_use]
+    pub const fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
 
-            if let Some(api_key) = api_key {
-                request = request.header("Authorization", &format!("Bearer {api_key}"));
-            }
+    /// Replaces the default retry policy for rate-limited or failed requests.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
 
-            request.call()
-        })
-        .await?;
+    /// Libraries with documentation in the cache, empty when caching is off.
+    #[must_use]
+    pub fn cached_libraries(&self) -> Vec<String> {
+        self.cache
+            .as_deref()
+            .map(|cache| cache.cached_libraries(self.api_key.as_deref()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|library_id| format!("/{}", library_id.trim_start_matches('/')))
+            .collect()
+    }
 
-        match result {
-            Ok(mut response) => {
-                let search_response: SearchResponse = response.body_mut().read_json()?;
-                Ok(search_response)
-            }
-            Err(Error::StatusCode(429)) => Ok(SearchResponse {
-                results: vec![],
-                error: Some(
-                    "Rate limited due to too many requests. Please try again later.".to_string(),
-                ),
-            }),
-            Err(Error::StatusCode(401)) => Ok(SearchResponse {
-                results: vec![],
-                error: Some("Unauthorized. Please check your API key.".to_string()),
-            }),
-            Err(e) => Ok(SearchResponse {
-                results: vec![],
-                error: Some(format!("Failed to search libraries: {e}")),
-            }),
+    pub async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        if self.offline {
+            let query = query.to_string();
+            let api_key = self.api_key.clone();
+            return self
+                .with_cache_blocking(move |cache| {
+                    Self::search_offline(cache, &query, api_key.as_deref())
+                })
+                .await
+                .unwrap_or_else(|| Err(Context7Error::Offline(OFFLINE_WITHOUT_CACHE.to_string())));
         }
+
+        let url = format!("{}/v1/search", self.base_url);
+
+        let body = self
+            .get(url, vec![("query", query.to_string())], vec![], &self.retry)
+            .await?;
+        let search_response: SearchResponse = serde_json::from_str(&body)?;
+
+        let stored = {
+            let query = query.to_string();
+            let api_key = self.api_key.clone();
+            let results = search_response.results.clone();
+            self.with_cache_blocking(move |cache| {
+                cache.put_search(&query, api_key.as_deref(), &results)
+            })
+            .await
+        };
+        if let Some(Err(e)) = stored {
+            tracing::warn!("Failed to cache search results for {query}: {e}");
+        }
+        Ok(search_response)
+    }
+
+    /// Searches once with a short deadline, for completing partially typed names.
+    ///
+    /// Results are not cached, since partial queries are unlikely to be repeated.
+    pub async fn suggest_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        if self.offline {
+            return self.search_libraries(query).await;
+        }
+
+        let url = format!("{}/v1/search", self.base_url);
+        let retry = RetryPolicy {
+            deadline: SUGGEST_DEADLINE,
+            ..RetryPolicy::none()
+        };
+        let body = self
+            .get(url, vec![("query", query.to_string())], vec![], &retry)
+            .await?;
+        Ok(serde_json::from_str(&body)?)
     }
 
     pub async fn fetch_library_documentation(
@@ -105,72 +197,347 @@ impl Context7Client {
         library_id: &str,
         tokens: Option<u32>,
         topic: Option<&str>,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<String>, Context7Error> {
+        self.fetch(library_id, tokens, topic, true).await
+    }
+
+    /// Fetches documentation from Context7 even when it is cached, updating the cache.
+    pub async fn refresh_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        self.fetch(library_id, tokens, topic, false).await
+    }
+
+    async fn fetch(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+        use_cache: bool,
+    ) -> Result<Option<String>, Context7Error> {
         let library_id = library_id.strip_prefix('/').unwrap_or(library_id);
         let url = format!("{}/v1/{}", self.base_url, library_id);
 
         let tokens = tokens.unwrap_or(DEFAULT_TOKENS).max(MINIMUM_TOKENS);
 
-        let api_key = self.api_key.clone();
-        let topic = topic.map(std::string::ToString::to_string);
-        let insecure = self.insecure;
-
-        let result = tokio::task::spawn_blocking(move || {
-            let agent = if insecure {
-                // Create agent that disables certificate verification for corporate MITM environments
-                let tls_config = TlsConfig::builder()
-                    .root_certs(RootCerts::PlatformVerifier) // Use platform's root certs (including corporate)
-                    .disable_verification(true)              // Disable all TLS verification (insecure)
-                    .build();
-
-                Agent::config_builder()
-                    .tls_config(tls_config)
-                    .build()
-                    .new_agent()
-            } else {
-                ureq::agent()
-            };
+        let key = OwnedDocsKey {
+            library_id: library_id.to_string(),
+            topic: topic.map(ToString::to_string),
+            tokens,
+            api_key: self.api_key.clone(),
+        };
+        if self.offline {
+            return self
+                .with_cache_blocking(move |cache| Self::fetch_offline(cache, &key.borrow()))
+                .await
+                .unwrap_or_else(|| Err(Context7Error::Offline(OFFLINE_WITHOUT_CACHE.to_string())))
+                .map(Some);
+        }
+        if use_cache
+            && let Some(content) = self
+                .with_cache_blocking({
+                    let key = key.clone();
+                    move |cache| cache.get(&key.borrow())
+                })
+                .await
+                .flatten()
+        {
+            tracing::debug!("Cache hit for {library_id}");
+            return Ok(Some(content));
+        }
 
-            let mut request = agent
-                .get(&url)
-                .query("tokens", tokens.to_string())
-                .query("type", "txt");
+        let mut query = vec![("tokens", tokens.to_string()), ("type", "txt".to_string())];
+        if let Some(topic) = topic {
+            query.push(("topic", topic.to_string()));
+        }
+        let headers = vec![("X-Context7-Source", "mcp-server".to_string())];
+
+        let text = self.get(url, query, headers, &self.retry).await?;
+        if text.is_empty() || text == "No content available" || text == "No context data available"
+        {
+            return Ok(None);
+        }
 
-            if let Some(topic) = topic {
-                request = request.query("topic", &topic);
+        let stored = {
+            let text = text.clone();
+            self.with_cache_blocking(move |cache| cache.put(&key.borrow(), &text))
+                .await
+        };
+        if let Some(Err(e)) = stored {
+            tracing::warn!("Failed to cache documentation for {library_id}: {e}");
+        }
+        Ok(Some(text))
+    }
+
+    /// Runs blocking cache I/O on the blocking thread pool; `None` without a cache.
+    async fn with_cache_blocking<T: Send + 'static>(
+        &self,
+        task: impl FnOnce(&DocsCache) -> T + Send + 'static,
+    ) -> Option<T> {
+        let cache = Arc::clone(self.cache.as_ref()?);
+        let span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || span.in_scope(|| task(&cache)))
+            .await
+            .ok()
+    }
+
+    /// Performs a GET with retries, returning the body of a successful response.
+    ///
+    /// Rate limiting, server errors and dropped connections are retried with backoff
+    /// until the policy's deadline; the last failure is reported as-is.
+    async fn get(
+        &self,
+        url: String,
+        query: Vec<(&'static str, String)>,
+        headers: Vec<(&'static str, String)>,
+        retry: &RetryPolicy,
+    ) -> Result<String, Context7Error> {
+        let deadline = Instant::now() + retry.deadline;
+        let mut attempt = 0;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let result = self
+                .get_once(url.clone(), query.clone(), headers.clone(), remaining)
+                .await;
+
+            if let Ok(response) = &result {
+                tracing::debug!("GET {url} returned HTTP {}", response.status);
             }
+            let (retry_after, failure) = match result {
+                Ok(response) if (200..300).contains(&response.status) => return Ok(response.body),
+                Ok(response) if is_retryable_status(response.status) => (
+                    response.retry_after,
+                    Context7Error::from_status(response.status, response.retry_after),
+                ),
+                Ok(response) => return Err(Context7Error::from_status(response.status, None)),
+                Err(e) if is_retryable_error(&e) => (None, e.into()),
+                Err(e) => return Err(e.into()),
+            };
+
+            let delay = retry
+                .delay(attempt, retry_after)
+                .filter(|delay| Instant::now() + *delay < deadline);
+            let Some(delay) = delay else {
+                return Err(failure);
+            };
+
+            attempt += 1;
+            tracing::debug!("Retrying {url} in {delay:?} (attempt {attempt})");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn get_once(
+        &self,
+        url: String,
+        query: Vec<(&'static str, String)>,
+        headers: Vec<(&'static str, String)>,
+        timeout: Duration,
+    ) -> Result<HttpResponse, Error> {
+        let api_key = self.api_key.clone();
+        let agent = self.agent.clone();
+        // Dropping this future, e.g. when the tool call is cancelled, stops the
+        // blocking request at its next read instead of downloading the rest.
+        let cancelled = CancellationToken::new();
+        let _cancel_on_drop = cancelled.clone().drop_guard();
+
+        tokio::task::spawn_blocking(move || {
+            let mut request = agent.get(&url).query_pairs(query);
 
             if let Some(api_key) = api_key {
                 request = request.header("Authorization", &format!("Bearer {api_key}"));
             }
 
-            request = request.header("X-Context7-Source", "mcp-server");
+            for (name, value) in headers {
+                request = request.header(name, &value);
+            }
+
+            let mut response = request
+                .config()
+                .timeout_global(Some(timeout))
+                .build()
+                .call()?;
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
 
-            request.call()
+            Ok(HttpResponse {
+                status: response.status().as_u16(),
+                retry_after,
+                body: read_body(response.body_mut(), &cancelled)?,
+            })
         })
-        .await?;
-
-        match result {
-            Ok(mut response) => {
-                let text = response.body_mut().read_to_string()?;
-                if text.is_empty() || text == "No content available" || text == "No context data available" {
-                    Ok(None)
-                } else {
-                    Ok(Some(text))
-                }
-            }
-            Err(Error::StatusCode(429)) => {
-                Ok(Some("Rate limited due to too many requests. Please try again later.".to_string()))
-            }
-            Err(Error::StatusCode(404)) => {
-                Ok(Some("The library you are trying to access does not exist. Please try with a different library ID.".to_string()))
-            }
-            Err(Error::StatusCode(401)) => {
-                Ok(Some("Unauthorized. Please check your API key.".to_string()))
-            }
-            Err(e) => {
-                Ok(Some(format!("Failed to fetch documentation: {e}")))
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+
+    /// Answers a search from the libraries and search results cached for `api_key`.
+    fn search_offline(
+        cache: &DocsCache,
+        query: &str,
+        api_key: Option<&str>,
+    ) -> Result<SearchResponse, Context7Error> {
+        let needle = query.to_lowercase();
+        let matches = |text: &str| text.to_lowercase().contains(&needle);
+        let libraries = cache.cached_libraries(api_key);
+        let is_cached = |id: &str| {
+            let id = id.strip_prefix('/').unwrap_or(id);
+            libraries.iter().any(|library| library == id)
+        };
+
+        let mut results: Vec<SearchResult> = cache
+            .cached_search_results(api_key)
+            .into_iter()
+            .filter(|result| {
+                (matches(&result.id) || matches(&result.title)) && is_cached(&result.id)
+            })
+            .collect();
+
+        for library in &libraries {
+            let id = format!("/{library}");
+            if matches(&id) && !results.iter().any(|result| result.id == id) {
+                results.push(SearchResult {
+                    id,
+                    title: library.clone(),
+                    description: "Available offline from the local cache.".to_string(),
+                    total_snippets: None,
+                    trust_score: None,
+                    versions: None,
+                    origin: None,
+                });
             }
         }
+
+        Ok(SearchResponse {
+            results,
+            error: None,
+        })
+    }
+
+    /// Serves documentation from the cache regardless of age, explaining what is
+    /// available offline when the requested documentation is not.
+    fn fetch_offline(cache: &DocsCache, key: &DocsKey<'_>) -> Result<String, Context7Error> {
+        if let Some(content) = cache.find_stale(key) {
+            tracing::debug!("Offline cache hit for {}", key.library_id);
+            return Ok(content);
+        }
+
+        let library = format!("/{}", key.library_id);
+        let requested = key
+            .topic
+            .map_or_else(String::new, |topic| format!(" on topic '{topic}'"));
+        let mut topics = cache.cached_topics(key.library_id, key.api_key);
+
+        if topics.is_empty() {
+            let libraries = cache.cached_libraries(key.api_key);
+            let available = if libraries.is_empty() {
+                "The offline cache is empty.".to_string()
+            } else {
+                format!(
+                    "Libraries available offline: {}",
+                    libraries
+                        .iter()
+                        .map(|library| format!("/{library}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            return Err(Context7Error::Offline(format!(
+                "Documentation for {library}{requested} is not available offline. {available}"
+            )));
+        }
+
+        // Topics sharing words with the requested one are the most likely substitutes.
+        let wanted = key.topic.unwrap_or_default().to_lowercase();
+        let is_nearby = |topic: &Option<String>| {
+            let topic = topic.as_deref().unwrap_or_default().to_lowercase();
+            !wanted.is_empty()
+                && !topic.is_empty()
+                && (topic.contains(&wanted) || wanted.contains(&topic))
+        };
+        topics.sort_by_key(|topic| !is_nearby(topic));
+        let topics: Vec<String> = topics
+            .into_iter()
+            .map(|topic| {
+                topic.map_or_else(
+                    || "(general documentation, no topic)".to_string(),
+                    |t| format!("'{t}'"),
+                )
+            })
+            .collect();
+
+        Err(Context7Error::Offline(format!(
+            "Documentation for {library}{requested} is not available offline. Cached topics for this library: {}",
+            topics.join(", ")
+        )))
+    }
+}
+
+/// A [`DocsKey`] that owns its parts, so it can move into blocking cache tasks.
+#[derive(Debug, Clone)]
+struct OwnedDocsKey {
+    library_id: String,
+    topic: Option<String>,
+    tokens: u32,
+    api_key: Option<String>,
+}
+
+impl OwnedDocsKey {
+    fn borrow(&self) -> DocsKey<'_> {
+        DocsKey {
+            library_id: &self.library_id,
+            topic: self.topic.as_deref(),
+            tokens: self.tokens,
+            api_key: self.api_key.as_deref(),
+        }
+    }
+}
+
+/// Reads a response body in chunks, abandoning the connection once `cancelled` fires.
+fn read_body(body: &mut Body, cancelled: &CancellationToken) -> Result<String, Error> {
+    let mut reader = body.with_config().limit(MAX_BODY_SIZE).reader();
+    let mut bytes = Vec::new();
+    let mut chunk = [0; 16 * 1024];
+    loop {
+        if cancelled.is_cancelled() {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "request cancelled",
+            )));
+        }
+        match reader.read(&mut chunk)? {
+            0 => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            read => bytes.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+/// Builds the agent once per client so connections and TLS sessions are pooled.
+fn build_agent(insecure: bool, config: &HttpConfig) -> Agent {
+    let builder = Agent::config_builder()
+        .http_status_as_error(false)
+        .max_idle_connections(config.max_idle_connections)
+        .max_idle_connections_per_host(config.max_idle_connections_per_host)
+        .max_idle_age(config.idle_timeout)
+        .timeout_connect(config.connect_timeout)
+        .timeout_recv_response(config.read_timeout)
+        .timeout_recv_body(config.read_timeout);
+
+    if insecure {
+        // Create agent that disables certificate verification for corporate MITM environments
+        let tls_config = TlsConfig::builder()
+            .root_certs(RootCerts::PlatformVerifier) // Use platform's root certs (including corporate)
+            .disable_verification(true) // Disable all TLS verification (insecure)
+            .build();
+
+        builder.tls_config(tls_config).build().new_agent()
+    } else {
+        builder.build().new_agent()
     }
 }
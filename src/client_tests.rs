@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::Context7Client;
+    use crate::{Context7Client, HttpConfig};
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
 
     #[tokio::test]
     async fn test_client_initialization() {
@@ -37,4 +41,81 @@ mod tests {
 
         // Should not panic during initialization
     }
+
+    /// Minimal keep-alive HTTP server that counts accepted connections.
+    fn spawn_counting_server(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    loop {
+                        let mut line = String::new();
+                        loop {
+                            line.clear();
+                            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                                return;
+                            }
+                            if line == "\r\n" {
+                                break;
+                            }
+                        }
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                            body.len()
+                        );
+                        if stream.write_all(response.as_bytes()).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        (base_url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_client_reuses_pooled_connection() {
+        let (base_url, connections) = spawn_counting_server("# Pooled docs");
+        let client = Context7Client::new_with_base_url(None, base_url, false);
+
+        for _ in 0..3 {
+            let docs = client
+                .fetch_library_documentation("/test/pooled", None, None)
+                .await
+                .unwrap();
+            assert_eq!(docs.as_deref(), Some("# Pooled docs"));
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_without_idle_pool_reconnects() {
+        let (base_url, connections) = spawn_counting_server("# Fresh docs");
+        let client = Context7Client::new_with_base_url(None, base_url, false).with_http_config(
+            &HttpConfig {
+                max_idle_connections: 0,
+                max_idle_connections_per_host: 0,
+                idle_timeout: Duration::ZERO,
+                ..HttpConfig::default()
+            },
+        );
+
+        for _ in 0..2 {
+            client
+                .fetch_library_documentation("/test/fresh", None, None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,141 @@
+use rmcp::model::CompletionInfo;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How many distinct headings are remembered per library.
+const TOPICS_PER_LIBRARY: usize = 500;
+
+/// Headings seen in fetched documentation, used to complete `topic` arguments.
+#[derive(Debug, Default)]
+pub struct TopicIndex {
+    headings: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl TopicIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers the headings of documentation fetched for `library_id`.
+    ///
+    /// Versions of a library share one set of topics.
+    pub fn record(&self, library_id: &str, documentation: &str) {
+        let Ok(mut headings) = self.headings.lock() else {
+            return;
+        };
+        let known = headings.entry(base_library_id(library_id)).or_default();
+        for heading in document_headings(documentation) {
+            if known.len() >= TOPICS_PER_LIBRARY {
+                break;
+            }
+            if !known.contains(&heading) {
+                known.push(heading);
+            }
+        }
+    }
+
+    /// Topics seen for `library`, an ID or a bare project name; every library's when `None`.
+    #[must_use]
+    pub fn topics(&self, library: Option<&str>) -> Vec<String> {
+        let Ok(headings) = self.headings.lock() else {
+            return Vec::new();
+        };
+        let wanted = library.map(|library| {
+            if library.starts_with('/') {
+                base_library_id(library)
+            } else {
+                library.trim().to_string()
+            }
+        });
+        let mut libraries: Vec<_> = headings
+            .iter()
+            .filter(|(id, _)| match &wanted {
+                None => true,
+                Some(wanted) => {
+                    *id == wanted
+                        || id
+                            .rsplit('/')
+                            .next()
+                            .is_some_and(|project| project.eq_ignore_ascii_case(wanted))
+                }
+            })
+            .collect();
+        libraries.sort_by(|a, b| a.0.cmp(b.0));
+        libraries
+            .into_iter()
+            .flat_map(|(_, topics)| topics.iter().cloned())
+            .collect()
+    }
+}
+
+/// The `/org/project` part of a possibly versioned library ID.
+fn base_library_id(library_id: &str) -> String {
+    let segments: Vec<&str> = library_id.trim_matches('/').split('/').take(2).collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Section titles in documentation: Markdown headings outside code blocks and
+/// the `TITLE:` lines of Context7 snippets.
+#[must_use]
+pub fn document_headings(documentation: &str) -> Vec<String> {
+    let mut headings = Vec::new();
+    let mut in_code = false;
+    for line in documentation.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let heading = line.strip_prefix("TITLE:").or_else(|| {
+            let level = line.chars().take_while(|&c| c == '#').count();
+            (1..=6)
+                .contains(&level)
+                .then(|| &line[level..])
+                .filter(|rest| rest.starts_with(' '))
+                .map(|rest| rest.trim_end_matches('#'))
+        });
+        if let Some(heading) = heading.map(str::trim).filter(|h| !h.is_empty())
+            && !headings.iter().any(|seen| seen == heading)
+        {
+            headings.push(heading.to_string());
+        }
+    }
+    headings
+}
+
+/// Candidates containing `value`, ignoring case, with prefix matches first.
+#[must_use]
+pub fn matching_values(candidates: impl IntoIterator<Item = String>, value: &str) -> Vec<String> {
+    let value = value.trim().to_lowercase();
+    let mut prefixed = Vec::new();
+    let mut containing = Vec::new();
+    for candidate in candidates {
+        let lower = candidate.to_lowercase();
+        if prefixed.contains(&candidate) || containing.contains(&candidate) {
+            continue;
+        }
+        if lower.starts_with(&value) {
+            prefixed.push(candidate);
+        } else if lower.contains(&value) {
+            containing.push(candidate);
+        }
+    }
+    prefixed.extend(containing);
+    prefixed
+}
+
+/// Wraps suggestions in a completion result, keeping within the MCP limit.
+#[must_use]
+pub fn completion_info(mut values: Vec<String>) -> CompletionInfo {
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    CompletionInfo {
+        has_more: Some(total > values.len()),
+        total: u32::try_from(total).ok(),
+        values,
+    }
+}
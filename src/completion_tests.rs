@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use crate::{TopicIndex, completion_info, document_headings, matching_values};
+
+    #[test]
+    fn test_document_headings_skip_code_blocks() {
+        let documentation = "# Routing\n\nIntro\n\n```bash\n# not a heading\n```\n\n## Dynamic Routes ##\n\n#hashtag\n\nTITLE: Linking Between Pages\nDESCRIPTION: Use Link.\n\n## Routing\n";
+        assert_eq!(
+            document_headings(documentation),
+            ["Routing", "Dynamic Routes", "Linking Between Pages"]
+        );
+    }
+
+    #[test]
+    fn test_topic_index_groups_versions_of_a_library() {
+        let topics = TopicIndex::new();
+        topics.record("/vercel/next.js", "## Routing\n");
+        topics.record("/vercel/next.js/v14.2.0", "## Caching\n## Routing\n");
+        topics.record("/facebook/react", "## Hooks\n");
+
+        assert_eq!(
+            topics.topics(Some("/vercel/next.js/v13")),
+            ["Routing", "Caching"]
+        );
+        assert_eq!(topics.topics(Some("React")), ["Hooks"]);
+        assert_eq!(topics.topics(None), ["Hooks", "Routing", "Caching"]);
+        assert!(topics.topics(Some("/unknown/lib")).is_empty());
+    }
+
+    #[test]
+    fn test_matching_values_prefers_prefix_matches() {
+        let candidates =
+            ["Server Actions", "Routing", "Dynamic Routes", "routing"].map(ToString::to_string);
+        assert_eq!(
+            matching_values(candidates, "rout"),
+            ["Routing", "routing", "Dynamic Routes"]
+        );
+    }
+
+    #[test]
+    fn test_completion_info_caps_values() {
+        let info = completion_info((0..150).map(|i| i.to_string()).collect());
+        assert_eq!(info.values.len(), 100);
+        assert_eq!(info.total, Some(150));
+        assert_eq!(info.has_more, Some(true));
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+/// Why a Context7 search or documentation fetch failed.
+#[derive(Debug, thiserror::Error)]
+pub enum Context7Error {
+    #[error("Rate limited due to too many requests. Please try again later.")]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("Unauthorized. Please check your API key.")]
+    Unauthorized,
+
+    #[error(
+        "The library you are trying to access does not exist. Please try with a different library ID."
+    )]
+    NotFound,
+
+    #[error("Context7 responded with HTTP status {status}.")]
+    HttpStatus { status: u16 },
+
+    #[error("Request to Context7 timed out: {0}")]
+    Timeout(String),
+
+    #[error("Could not reach Context7: {0}")]
+    Network(String),
+
+    #[error("TLS error talking to Context7: {0}")]
+    Tls(String),
+
+    #[error("Could not decode the Context7 response: {0}")]
+    Decode(String),
+
+    #[error("{0}")]
+    Offline(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl Context7Error {
+    /// Maps a non-success HTTP status to the matching error.
+    #[must_use]
+    pub const fn from_status(status: u16, retry_after: Option<Duration>) -> Self {
+        match status {
+            429 => Self::RateLimited { retry_after },
+            401 | 403 => Self::Unauthorized,
+            404 => Self::NotFound,
+            status => Self::HttpStatus { status },
+        }
+    }
+
+    /// Stable, machine-readable identifier reported to MCP clients.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Unauthorized => "unauthorized",
+            Self::NotFound => "not_found",
+            Self::HttpStatus { .. } => "http_status",
+            Self::Timeout(_) => "timeout",
+            Self::Network(_) => "network",
+            Self::Tls(_) => "tls",
+            Self::Decode(_) => "decode",
+            Self::Offline(_) => "offline_unavailable",
+            Self::Internal(_) => "internal",
+        }
+    }
+}
+
+impl From<ureq::Error> for Context7Error {
+    fn from(error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::StatusCode(status) => Self::from_status(status, None),
+            ureq::Error::Timeout(_) => Self::Timeout(error.to_string()),
+            ureq::Error::Tls(_) | ureq::Error::Rustls(_) => Self::Tls(error.to_string()),
+            ureq::Error::Json(_) => Self::Decode(error.to_string()),
+            _ => Self::Network(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Context7Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Decode(error.to_string())
+    }
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::sync::Arc;
+
+use crate::backend::DocsBackend;
+use crate::client::{SearchResponse, SearchResult};
+use crate::error::Context7Error;
+
+/// A named backend taking part in a federation.
+struct Source {
+    name: String,
+    backend: Arc<dyn DocsBackend>,
+}
+
+/// Combines several backends into one.
+///
+/// Searches run on every source concurrently and are merged into a single ranked
+/// list; fetches go to the first source, in the order they were added, that
+/// knows the library.
+#[derive(Default)]
+pub struct FederatedBackend {
+    sources: Vec<Source>,
+}
+
+impl FederatedBackend {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source; `name` is reported as the origin of its search results.
+    #[must_use]
+    pub fn with_source(mut self, name: impl Into<String>, backend: Arc<dyn DocsBackend>) -> Self {
+        self.sources.push(Source {
+            name: name.into(),
+            backend,
+        });
+        self
+    }
+
+    /// Searches every source at once, merging what they find into one ranking.
+    async fn search(&self, query: &str, suggest: bool) -> Result<SearchResponse, Context7Error> {
+        let responses = join_all(self.sources.iter().map(|source| async move {
+            if suggest {
+                source.backend.suggest_libraries(query).await
+            } else {
+                source.backend.search_libraries(query).await
+            }
+        }))
+        .await;
+
+        let mut candidates = Vec::new();
+        let mut first_error = None;
+        for (source, response) in self.sources.iter().zip(responses) {
+            match response {
+                Ok(response) => {
+                    if let Some(error) = response.error {
+                        tracing::warn!("Search on {} reported: {error}", source.name);
+                    }
+                    candidates.extend(response.results.into_iter().enumerate().map(
+                        |(position, mut result)| {
+                            result.origin = Some(source.name.clone());
+                            let score = relevance(query, &result, position);
+                            (score, result)
+                        },
+                    ));
+                }
+                Err(e) => {
+                    tracing::warn!("Search on {} failed: {e}", source.name);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        // Only fail when no source could answer at all.
+        if candidates.is_empty()
+            && let Some(error) = first_error
+        {
+            return Err(error);
+        }
+
+        Ok(SearchResponse {
+            results: merge_ranked(candidates),
+            error: None,
+        })
+    }
+
+    /// Fetches from the first source that knows the library.
+    ///
+    /// Only `NotFound` moves on to the next source. Any other answer, including
+    /// "no documentation for this topic" or an error, is final, so IDs served
+    /// locally are never sent to later, remote sources.
+    async fn fetch(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+        refresh: bool,
+    ) -> Result<Option<String>, Context7Error> {
+        for source in &self.sources {
+            let fetched = if refresh {
+                source
+                    .backend
+                    .refresh_library_documentation(library_id, tokens, topic)
+                    .await
+            } else {
+                source
+                    .backend
+                    .fetch_library_documentation(library_id, tokens, topic)
+                    .await
+            };
+            match fetched {
+                Err(Context7Error::NotFound) => {}
+                Err(e) => {
+                    tracing::warn!("Fetching {library_id} from {} failed: {e}", source.name);
+                    return Err(e);
+                }
+                documentation => return documentation,
+            }
+        }
+        Err(Context7Error::NotFound)
+    }
+}
+
+#[async_trait]
+impl DocsBackend for FederatedBackend {
+    async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        self.search(query, false).await
+    }
+
+    async fn suggest_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        self.search(query, true).await
+    }
+
+    async fn fetch_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        self.fetch(library_id, tokens, topic, false).await
+    }
+
+    async fn refresh_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        self.fetch(library_id, tokens, topic, true).await
+    }
+
+    fn cached_libraries(&self) -> Vec<String> {
+        let mut libraries: Vec<String> = Vec::new();
+        for library in self
+            .sources
+            .iter()
+            .flat_map(|source| source.backend.cached_libraries())
+        {
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+        libraries
+    }
+}
+
+/// Scores a result by name match first, then source rank, trust and coverage.
+fn relevance(query: &str, result: &SearchResult, position: usize) -> f64 {
+    let query = query.trim().to_lowercase();
+    let title = result.title.to_lowercase();
+    let project = result
+        .id
+        .trim_matches('/')
+        .split('/')
+        .nth(1)
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let name = if title == query || project == query {
+        1.0
+    } else if title.starts_with(&query) || project.starts_with(&query) {
+        0.7
+    } else if title.contains(&query) || project.contains(&query) {
+        0.5
+    } else {
+        0.1
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let rank = 1.0 / (1.0 + position as f64);
+    let trust = result.trust_score.filter(|t| *t >= 0.0).unwrap_or(0.0) / 10.0;
+    let snippets = result
+        .total_snippets
+        .filter(|s| *s > 0)
+        .map_or(0.0, |s| f64::from(s).ln_1p() / 10.0);
+
+    3.0f64.mul_add(name, rank + trust + snippets)
+}
+
+/// Sorts by score and folds results naming the same library into the best one.
+fn merge_ranked(mut candidates: Vec<(f64, SearchResult)>) -> Vec<SearchResult> {
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut merged: Vec<SearchResult> = Vec::new();
+    for (_, result) in candidates {
+        let key = library_key(&result.id);
+        let Some(existing) = merged.iter_mut().find(|r| library_key(&r.id) == key) else {
+            merged.push(result);
+            continue;
+        };
+
+        if let Some(origin) = result.origin
+            && let Some(existing_origin) = &mut existing.origin
+            && !existing_origin.split(", ").any(|o| o == origin)
+        {
+            existing_origin.push_str(", ");
+            existing_origin.push_str(&origin);
+        }
+        if let Some(versions) = result.versions {
+            let existing_versions = existing.versions.get_or_insert_with(Vec::new);
+            for version in versions {
+                if !existing_versions.contains(&version) {
+                    existing_versions.push(version);
+                }
+            }
+        }
+        existing.total_snippets = existing.total_snippets.max(result.total_snippets);
+        existing.trust_score = match (existing.trust_score, result.trust_score) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    merged
+}
+
+fn library_key(id: &str) -> String {
+    id.trim_matches('/').to_lowercase()
+}
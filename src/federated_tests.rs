@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Context7Error, DocsBackend, FederatedBackend, LocalDocsBackend, LocalSource,
+        RustdocBackend, SearchResponse, SearchResult, format_search_results,
+    };
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    /// Returns fixed results and documentation for a fixed set of IDs.
+    struct FixedBackend {
+        results: Vec<(&'static str, &'static str, Option<f64>)>,
+        docs: Vec<(&'static str, &'static str)>,
+        delay: Duration,
+        fail: bool,
+    }
+
+    impl FixedBackend {
+        fn new(results: Vec<(&'static str, &'static str, Option<f64>)>) -> Self {
+            Self {
+                results,
+                docs: vec![],
+                delay: Duration::ZERO,
+                fail: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl DocsBackend for FixedBackend {
+        async fn search_libraries(&self, _query: &str) -> Result<SearchResponse, Context7Error> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(Context7Error::Network("unreachable".to_string()));
+            }
+            Ok(SearchResponse {
+                results: self
+                    .results
+                    .iter()
+                    .map(|(id, title, trust_score)| SearchResult {
+                        id: (*id).to_string(),
+                        title: (*title).to_string(),
+                        description: String::new(),
+                        total_snippets: None,
+                        trust_score: *trust_score,
+                        versions: Some(vec![format!("{title}-v1")]),
+                        origin: None,
+                    })
+                    .collect(),
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            if self.fail {
+                return Err(Context7Error::Network("unreachable".to_string()));
+            }
+            self.docs
+                .iter()
+                .find(|(id, _)| *id == library_id)
+                .map(|(_, docs)| Some((*docs).to_string()))
+                .ok_or(Context7Error::NotFound)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_merges_ranks_and_tags_origin() {
+        let remote = FixedBackend::new(vec![
+            ("/other/axum-extra", "axum-extra", Some(9.0)),
+            ("/tokio-rs/axum", "axum", Some(8.0)),
+        ]);
+        let mirror = FixedBackend::new(vec![("/tokio-rs/axum/", "axum", Some(9.5))]);
+        let backend = FederatedBackend::new()
+            .with_source("context7", Arc::new(remote))
+            .with_source("mirror", Arc::new(mirror));
+
+        let response = backend.search_libraries("axum").await.unwrap();
+
+        assert_eq!(response.results.len(), 2);
+        let axum = &response.results[0];
+        assert_eq!(axum.id, "/tokio-rs/axum/");
+        assert_eq!(axum.origin.as_deref(), Some("mirror, context7"));
+        assert_eq!(axum.trust_score, Some(9.5));
+        assert_eq!(axum.versions.as_ref().unwrap().len(), 1);
+        assert_eq!(response.results[1].id, "/other/axum-extra");
+
+        assert!(format_search_results(&response).contains("- Source: mirror, context7"));
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_queries_sources_concurrently() {
+        let slow = |id| FixedBackend {
+            delay: Duration::from_millis(300),
+            ..FixedBackend::new(vec![(id, id, None)])
+        };
+        let backend = FederatedBackend::new()
+            .with_source("a", Arc::new(slow("/a/a")))
+            .with_source("b", Arc::new(slow("/b/b")));
+
+        let started = std::time::Instant::now();
+        let response = backend.search_libraries("x").await.unwrap();
+
+        assert_eq!(response.results.len(), 2);
+        assert!(started.elapsed() < Duration::from_millis(550));
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_tolerates_partial_failure() {
+        let failing = FixedBackend {
+            fail: true,
+            ..FixedBackend::new(vec![])
+        };
+        let working = FixedBackend::new(vec![("/org/lib", "lib", None)]);
+
+        let backend = FederatedBackend::new()
+            .with_source("down", Arc::new(failing))
+            .with_source("up", Arc::new(working));
+        let response = backend.search_libraries("lib").await.unwrap();
+        assert_eq!(response.results.len(), 1);
+
+        let all_down = FederatedBackend::new().with_source(
+            "down",
+            Arc::new(FixedBackend {
+                fail: true,
+                ..FixedBackend::new(vec![])
+            }),
+        );
+        assert!(matches!(
+            all_down.search_libraries("lib").await,
+            Err(Context7Error::Network(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_federated_fetch_falls_through_sources() {
+        let local = FixedBackend {
+            docs: vec![("/org/internal", "# internal")],
+            ..FixedBackend::new(vec![])
+        };
+        let remote = FixedBackend {
+            docs: vec![("/vercel/next.js", "# next")],
+            ..FixedBackend::new(vec![])
+        };
+        let backend = FederatedBackend::new()
+            .with_source("local", Arc::new(local))
+            .with_source("context7", Arc::new(remote));
+
+        let fetch = |id: &'static str| backend.fetch_library_documentation(id, None, None);
+        assert_eq!(
+            fetch("/org/internal").await.unwrap().as_deref(),
+            Some("# internal")
+        );
+        assert_eq!(
+            fetch("/vercel/next.js").await.unwrap().as_deref(),
+            Some("# next")
+        );
+        assert!(matches!(
+            fetch("/missing/lib").await,
+            Err(Context7Error::NotFound)
+        ));
+    }
+
+    /// A remote source that counts the fetches reaching it.
+    #[derive(Default)]
+    struct RemoteBackend {
+        fetches: AtomicU32,
+    }
+
+    #[async_trait]
+    impl DocsBackend for RemoteBackend {
+        async fn search_libraries(&self, _query: &str) -> Result<SearchResponse, Context7Error> {
+            Ok(SearchResponse {
+                results: vec![],
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            _library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Err(Context7Error::RateLimited { retry_after: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_federated_fetch_keeps_local_ids_local() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "# Internal\n\nPrivate docs.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("mine.json"),
+            serde_json::json!({
+                "root": 0,
+                "index": {"0": {
+                    "id": 0, "name": "mine", "docs": "Private crate.",
+                    "inner": {"module": {"is_crate": true, "items": []}}
+                }}
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let local = LocalDocsBackend::new(vec![
+            LocalSource::parse(&format!("/org/internal={}", dir.path().display())).unwrap(),
+        ]);
+        let rustdoc = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+        let remote = Arc::new(RemoteBackend::default());
+        let backend = FederatedBackend::new()
+            .with_source("local", Arc::new(local))
+            .with_source("rustdoc", Arc::new(rustdoc))
+            .with_source("context7", remote.clone());
+
+        for id in ["/org/internal", "/crates/mine"] {
+            let docs = backend
+                .fetch_library_documentation(id, None, Some("nomatch"))
+                .await
+                .unwrap();
+            assert_eq!(docs, None);
+        }
+        assert_eq!(remote.fetches.load(Ordering::SeqCst), 0);
+
+        assert!(matches!(
+            backend
+                .fetch_library_documentation("/vercel/next.js", None, None)
+                .await,
+            Err(Context7Error::RateLimited { .. })
+        ));
+        assert_eq!(remote.fetches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,10 @@
-use crate::client::SearchResponse;
+use crate::client::{SearchResponse, SearchResult};
+use crate::error::Context7Error;
+use crate::project::ProjectLibrary;
+use crate::version::VersionMatch;
+
+/// What `get-library-docs` says when a library has no documentation.
+pub const DOCS_NOT_FOUND: &str = "Documentation not found or not finalized for this library. This might have happened because you used an invalid Context7-compatible library ID. To get a valid Context7-compatible library ID, use the 'resolve-library-id' with the package name you wish to retrieve documentation for.";
 
 #[must_use]
 pub fn format_search_results(response: &SearchResponse) -> String {
@@ -34,9 +40,95 @@ pub fn format_search_results(response: &SearchResponse) -> String {
                 parts.push(format!("- Versions: {}", versions.join(", ")));
             }
 
+            if let Some(origin) = &result.origin {
+                parts.push(format!("- Source: {origin}"));
+            }
+
             parts.join("\n")
         })
         .collect();
 
     formatted_results.join("\n----------\n")
 }
+
+/// Lists a project's dependencies with the library IDs they resolved to.
+#[must_use]
+pub fn format_project_libraries(root: &str, libraries: &[ProjectLibrary]) -> String {
+    if libraries.is_empty() {
+        return format!("No dependencies found in {root}.");
+    }
+
+    let lines: Vec<String> = libraries
+        .iter()
+        .map(|library| {
+            let dependency = &library.dependency;
+            let version = match (&dependency.locked_version, &dependency.requirement) {
+                (Some(locked), _) => format!(" {locked}"),
+                (None, Some(requirement)) => format!(" {requirement} (not locked)"),
+                (None, None) => String::new(),
+            };
+            let resolution = match &library.resolution {
+                Some(Ok(Some(library_id))) => library_id.clone(),
+                Some(Ok(None)) => "no matching library found".to_string(),
+                Some(Err(error)) => format!("lookup failed [{}]: {error}", error.code()),
+                None => "not looked up (too many dependencies or time limit reached)".to_string(),
+            };
+            format!(
+                "- {}{version} ({}, {}): {resolution}",
+                dependency.name, dependency.ecosystem, dependency.manifest
+            )
+        })
+        .collect();
+
+    format!("Project libraries in {root}:\n\n{}", lines.join("\n"))
+}
+
+/// Explains which `/org/project/version` ID was picked for a requested version.
+#[must_use]
+pub fn format_version_selection(
+    result: &SearchResult,
+    requested: &str,
+    selection: Option<&VersionMatch>,
+) -> String {
+    let id = result.id.trim_end_matches('/');
+    match selection {
+        Some(VersionMatch::Exact(version) | VersionMatch::InRange(version)) => format!(
+            "Best match for version {requested}: {id}/{version} ({})",
+            result.title
+        ),
+        Some(VersionMatch::Nearest(version)) => format!(
+            "Note: {} has no documentation matching version {requested}. The nearest available version is {version}: {id}/{version}",
+            result.title
+        ),
+        None => match result.versions.as_deref() {
+            Some(versions) if !versions.is_empty() => format!(
+                "Note: version {requested} could not be matched against the versions of {} ({}). Use {id} for the latest documentation.",
+                result.title,
+                versions.join(", ")
+            ),
+            _ => format!(
+                "Note: {} has no version-specific documentation. Use {id} for the latest documentation.",
+                result.title
+            ),
+        },
+    }
+}
+
+/// One library's section of a batched documentation fetch, headed by what was requested.
+#[must_use]
+pub fn format_library_docs_entry(
+    library_id: &str,
+    topic: Option<&str>,
+    result: &Result<Option<String>, Context7Error>,
+) -> String {
+    let heading = topic.map_or_else(
+        || format!("## {library_id}"),
+        |topic| format!("## {library_id} (topic: {topic})"),
+    );
+    let body = match result {
+        Ok(Some(documentation)) => documentation.clone(),
+        Ok(None) => DOCS_NOT_FOUND.to_string(),
+        Err(error) => format!("Error [{}]: {error}", error.code()),
+    };
+    format!("{heading}\n\n{body}")
+}
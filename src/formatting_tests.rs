@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::{SearchResponse, SearchResult, format_search_results};
+    use crate::{
+        Context7Error, DOCS_NOT_FOUND, SearchResponse, SearchResult, format_library_docs_entry,
+        format_search_results,
+    };
 
     #[tokio::test]
     async fn test_search_response_formatting() {
@@ -25,6 +28,7 @@ mod tests {
                     total_snippets: Some(100),
                     trust_score: Some(8.0),
                     versions: Some(vec!["1.0.0".to_string(), "2.0.0".to_string()]),
+                    origin: None,
                 },
                 SearchResult {
                     id: "/test/lib2".to_string(),
@@ -33,6 +37,7 @@ mod tests {
                     total_snippets: Some(-1), // Should be filtered out
                     trust_score: Some(-1.0),  // Should be filtered out
                     versions: None,
+                    origin: None,
                 },
             ],
             error: None,
@@ -56,4 +61,24 @@ mod tests {
         // Check separator
         assert!(formatted.contains("----------"));
     }
+
+    #[test]
+    fn test_library_docs_entry_formatting() {
+        assert_eq!(
+            format_library_docs_entry(
+                "/vercel/next.js",
+                Some("routing"),
+                &Ok(Some("# Routing".to_string()))
+            ),
+            "## /vercel/next.js (topic: routing)\n\n# Routing"
+        );
+        assert_eq!(
+            format_library_docs_entry("/missing/lib", None, &Ok(None)),
+            format!("## /missing/lib\n\n{DOCS_NOT_FOUND}")
+        );
+        assert_eq!(
+            format_library_docs_entry("/private/lib", None, &Err(Context7Error::Unauthorized)),
+            "## /private/lib\n\nError [unauthorized]: Unauthorized. Please check your API key."
+        );
+    }
 }
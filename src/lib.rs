@@ -1,18 +1,78 @@
+pub mod backend;
+pub mod budget;
+pub mod cache;
 pub mod client;
+pub mod completion;
+pub mod error;
+pub mod federated;
 pub mod formatting;
+pub mod local;
+pub mod logging;
+pub mod origin;
+pub mod project;
+pub mod prompts;
+pub mod resources;
+pub mod retry;
+pub mod rustdoc;
 pub mod server;
+pub mod tokens;
+pub mod tools;
+pub mod version;
 
+pub use backend::*;
+pub use budget::*;
+pub use cache::*;
 pub use client::*;
+pub use completion::*;
+pub use error::*;
+pub use federated::*;
 pub use formatting::*;
+pub use local::*;
+pub use logging::*;
+pub use origin::*;
+pub use project::*;
+pub use prompts::*;
+pub use resources::*;
+pub use retry::*;
+pub use rustdoc::*;
 pub use server::*;
+pub use tokens::*;
+pub use tools::*;
+pub use version::*;
 
 #[cfg(test)]
 mod api_tests;
 #[cfg(test)]
+mod budget_tests;
+#[cfg(test)]
+mod cache_tests;
+#[cfg(test)]
 mod client_tests;
 #[cfg(test)]
+mod completion_tests;
+#[cfg(test)]
+mod federated_tests;
+#[cfg(test)]
 mod formatting_tests;
 #[cfg(test)]
+mod local_tests;
+#[cfg(test)]
+mod origin_tests;
+#[cfg(test)]
+mod project_tests;
+#[cfg(test)]
+mod prompts_tests;
+#[cfg(test)]
+mod resources_tests;
+#[cfg(test)]
+mod rustdoc_tests;
+#[cfg(test)]
 mod security_tests;
 #[cfg(test)]
 mod server_tests;
+#[cfg(test)]
+mod tokens_tests;
+#[cfg(test)]
+mod tools_tests;
+#[cfg(test)]
+mod version_tests;
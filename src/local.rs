@@ -0,0 +1,348 @@
+use async_trait::async_trait;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::backend::DocsBackend;
+use crate::client::{DEFAULT_TOKENS, MINIMUM_TOKENS, SearchResponse, SearchResult};
+use crate::error::Context7Error;
+use crate::tokens::estimate_tokens;
+
+const DOC_EXTENSIONS: &[&str] = &["md", "markdown", "mdx", "txt"];
+pub(crate) const SECTION_SEPARATOR: &str = "\n\n----------\n\n";
+
+/// A local directory served under a Context7-style `/org/project` ID.
+#[derive(Debug, Clone)]
+pub struct LocalSource {
+    pub library_id: String,
+    pub dir: PathBuf,
+}
+
+impl LocalSource {
+    /// Parses `/org/project=DIR` as given to `--local-docs`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (library_id, dir) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected /org/project=DIR, got '{spec}'"))?;
+        let segments: Vec<&str> = library_id.trim_matches('/').split('/').collect();
+        if !library_id.starts_with('/')
+            || segments.len() != 2
+            || segments.iter().any(|s| s.is_empty())
+        {
+            return Err(format!(
+                "library ID must look like /org/project, got '{library_id}'"
+            ));
+        }
+        Ok(Self {
+            library_id: library_id.trim_end_matches('/').to_string(),
+            dir: PathBuf::from(dir),
+        })
+    }
+}
+
+/// One heading-delimited chunk of a document.
+#[derive(Debug)]
+struct Section {
+    heading: String,
+    source: String,
+    body: String,
+}
+
+/// Serves Markdown and text files from local directories.
+///
+/// Directories are re-read on every call so edits show up without a restart.
+pub struct LocalDocsBackend {
+    sources: Vec<LocalSource>,
+}
+
+impl LocalDocsBackend {
+    #[must_use]
+    pub const fn new(sources: Vec<LocalSource>) -> Self {
+        Self { sources }
+    }
+
+    fn source(&self, library_id: &str) -> Option<&LocalSource> {
+        let library_id = format!("/{}", library_id.trim_start_matches('/'));
+        self.sources.iter().find(|s| s.library_id == library_id)
+    }
+
+    /// Matches `query` against library IDs only, without reading any files.
+    fn suggest_local(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        self.sources
+            .iter()
+            .filter_map(|source| {
+                let project = source.library_id.rsplit('/').next().unwrap_or_default();
+                let matches = source.library_id.to_lowercase().contains(&query)
+                    || query.contains(&project.to_lowercase());
+                matches.then(|| SearchResult {
+                    id: source.library_id.clone(),
+                    title: project.to_string(),
+                    description: String::new(),
+                    total_snippets: None,
+                    trust_score: None,
+                    versions: None,
+                    origin: None,
+                })
+            })
+            .collect()
+    }
+
+    fn search_local(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.to_lowercase();
+        self.sources
+            .iter()
+            .filter_map(|source| {
+                let sections = read_sections(&source.dir);
+                let title = library_title(source, &sections);
+                let project = source.library_id.rsplit('/').next().unwrap_or_default();
+                let matches = source.library_id.to_lowercase().contains(&query)
+                    || title.to_lowercase().contains(&query)
+                    || query.contains(&project.to_lowercase());
+                if !matches {
+                    return None;
+                }
+
+                let snippets = sections
+                    .iter()
+                    .map(|section| section.body.matches("```").count() / 2)
+                    .sum::<usize>();
+                Some(SearchResult {
+                    id: source.library_id.clone(),
+                    title,
+                    description: library_description(source, &sections),
+                    total_snippets: i32::try_from(snippets).ok(),
+                    trust_score: None,
+                    versions: None,
+                    origin: None,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DocsBackend for LocalDocsBackend {
+    async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        let backend = Self::new(self.sources.clone());
+        let query = query.to_string();
+        let results = tokio::task::spawn_blocking(move || backend.search_local(&query))
+            .await
+            .map_err(|e| Context7Error::Internal(e.to_string()))?;
+        Ok(SearchResponse {
+            results,
+            error: None,
+        })
+    }
+
+    async fn suggest_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        Ok(SearchResponse {
+            results: self.suggest_local(query),
+            error: None,
+        })
+    }
+
+    async fn fetch_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        let Some(source) = self.source(library_id) else {
+            return Err(Context7Error::NotFound);
+        };
+
+        let tokens = tokens.unwrap_or(DEFAULT_TOKENS).max(MINIMUM_TOKENS);
+        let dir = source.dir.clone();
+        let topic = topic.map(str::to_lowercase);
+
+        tokio::task::spawn_blocking(move || {
+            select_sections(read_sections(&dir), topic.as_deref(), tokens)
+        })
+        .await
+        .map_err(|e| Context7Error::Internal(e.to_string()))
+    }
+}
+
+/// Picks the sections relevant to `topic`, best first, within the token budget.
+fn select_sections(sections: Vec<Section>, topic: Option<&str>, tokens: u32) -> Option<String> {
+    let ranked: Vec<(usize, Section)> = match topic {
+        Some(topic) => {
+            let words: Vec<&str> = topic.split_whitespace().collect();
+            let mut ranked: Vec<(usize, Section)> = sections
+                .into_iter()
+                .map(|section| {
+                    let heading = section.heading.to_lowercase();
+                    let body = section.body.to_lowercase();
+                    let score = words
+                        .iter()
+                        .map(|w| {
+                            3 * usize::from(heading.contains(w)) + usize::from(body.contains(w))
+                        })
+                        .sum();
+                    (score, section)
+                })
+                .filter(|(score, _)| *score > 0)
+                .collect();
+            ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            ranked
+        }
+        None => sections.into_iter().map(|section| (0, section)).collect(),
+    };
+
+    let budget = tokens as usize;
+    let mut used = 0;
+    let mut parts = Vec::new();
+    for (_, section) in ranked {
+        let text = format!(
+            "### {}\n\nSource: {}\n\n{}",
+            section.heading, section.source, section.body
+        );
+        let cost = estimate_tokens(&text);
+        if !parts.is_empty() && used + cost > budget {
+            break;
+        }
+        used += cost;
+        parts.push(text);
+    }
+
+    (!parts.is_empty()).then(|| parts.join(SECTION_SEPARATOR))
+}
+
+fn library_title(source: &LocalSource, sections: &[Section]) -> String {
+    sections
+        .iter()
+        .find(|section| is_index(&section.source) && section.heading != section.source)
+        .and_then(|section| section.heading.split(" > ").next())
+        .map_or_else(
+            || {
+                source
+                    .library_id
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            },
+            ToString::to_string,
+        )
+}
+
+fn library_description(source: &LocalSource, sections: &[Section]) -> String {
+    sections
+        .iter()
+        .filter(|section| is_index(&section.source))
+        .find_map(|section| {
+            section
+                .body
+                .split("\n\n")
+                .map(str::trim)
+                .find(|p| !p.is_empty() && !p.starts_with("```"))
+                .map(|p| p.replace('\n', " "))
+        })
+        .unwrap_or_else(|| format!("Local documentation from {}", source.dir.display()))
+}
+
+fn is_index(source: &str) -> bool {
+    let stem = Path::new(source)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    !source.contains('/') && (stem == "readme" || stem == "index")
+}
+
+/// Reads every document under `dir`, README/index files first, then by path.
+fn read_sections(dir: &Path) -> Vec<Section> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files);
+
+    let relative = |path: &Path| {
+        path.strip_prefix(dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    files.sort_by_key(|path| {
+        let rel = relative(path);
+        (!is_index(&rel), rel)
+    });
+
+    files
+        .iter()
+        .filter_map(|path| Some((relative(path), fs::read_to_string(path).ok()?)))
+        .flat_map(|(source, text)| split_sections(&source, &text))
+        .collect()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        // Symlinked directories are not followed, since they can form cycles.
+        if file_type.is_dir() {
+            collect_files(&path, files);
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| DOC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Splits a document on Markdown headings, ignoring `#` lines inside code fences.
+fn split_sections(source: &str, text: &str) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut body = String::new();
+    let mut in_fence = false;
+
+    let mut flush = |headings: &[(usize, String)], body: &mut String| {
+        let trimmed = body.trim();
+        if !trimmed.is_empty() {
+            let heading = if headings.is_empty() {
+                source.to_string()
+            } else {
+                headings
+                    .iter()
+                    .map(|(_, h)| h.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" > ")
+            };
+            sections.push(Section {
+                heading,
+                source: source.to_string(),
+                body: trimmed.to_string(),
+            });
+        }
+        body.clear();
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if !in_fence && (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush(&headings, &mut body);
+            headings.retain(|(l, _)| *l < level);
+            headings.push((level, trimmed[level..].trim().to_string()));
+        } else {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    flush(&headings, &mut body);
+
+    sections
+}
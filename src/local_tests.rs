@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use crate::{Context7Error, DocsBackend, LocalDocsBackend, LocalSource};
+    use std::fs;
+    use std::path::Path;
+
+    fn write_docs(dir: &Path) {
+        fs::write(
+            dir.join("README.md"),
+            "# Internal Lib\n\nShared helpers for our services.\n\n## Installation\n\nAdd `internal-lib` to your manifest.\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.join("guides")).unwrap();
+        fs::write(
+            dir.join("guides/routing.md"),
+            "# Routing\n\nDefine routes with `Router::new()`.\n\n```rust\n# not a heading\nlet router = Router::new();\n```\n\n## Middleware\n\nWrap routes with layers.\n",
+        )
+        .unwrap();
+    }
+
+    fn backend(dir: &Path) -> LocalDocsBackend {
+        LocalDocsBackend::new(vec![
+            LocalSource::parse(&format!("/org/internal-lib={}", dir.display())).unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_local_source_parse() {
+        let source = LocalSource::parse("/org/lib=./docs").unwrap();
+        assert_eq!(source.library_id, "/org/lib");
+        assert_eq!(source.dir, Path::new("./docs"));
+
+        assert!(LocalSource::parse("/org/lib").is_err());
+        assert!(LocalSource::parse("org/lib=./docs").is_err());
+        assert!(LocalSource::parse("/org=./docs").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_search_uses_readme_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        write_docs(dir.path());
+
+        let response = backend(dir.path())
+            .search_libraries("internal")
+            .await
+            .unwrap();
+
+        assert_eq!(response.results.len(), 1);
+        let result = &response.results[0];
+        assert_eq!(result.id, "/org/internal-lib");
+        assert_eq!(result.title, "Internal Lib");
+        assert_eq!(result.description, "Shared helpers for our services.");
+        assert_eq!(result.total_snippets, Some(1));
+
+        let none = backend(dir.path()).search_libraries("react").await.unwrap();
+        assert!(none.results.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_local_docs_skip_symlinked_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        write_docs(dir.path());
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("guides/loop")).unwrap();
+
+        let docs = backend(dir.path())
+            .fetch_library_documentation("/org/internal-lib", None, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(docs.matches("Define routes").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_suggestions_do_not_read_files() {
+        let missing = tempfile::tempdir().unwrap().path().join("gone");
+        let backend = backend(&missing);
+
+        let response = backend.suggest_libraries("intern").await.unwrap();
+
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].id, "/org/internal-lib");
+        assert_eq!(response.results[0].title, "internal-lib");
+        assert!(
+            backend
+                .suggest_libraries("react")
+                .await
+                .unwrap()
+                .results
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_fetch_filters_by_topic() {
+        let dir = tempfile::tempdir().unwrap();
+        write_docs(dir.path());
+
+        let docs = backend(dir.path())
+            .fetch_library_documentation("/org/internal-lib", None, Some("middleware"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(docs.starts_with("### Routing > Middleware"));
+        assert!(docs.contains("Source: guides/routing.md"));
+        assert!(!docs.contains("Installation"));
+
+        let missing = backend(dir.path())
+            .fetch_library_documentation("/org/internal-lib", None, Some("graphql"))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_fetch_respects_token_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = "word ".repeat(1000);
+        fs::write(
+            dir.path().join("README.md"),
+            format!("# One\n\n{big}\n\n# Two\n\n{big}\n\n# Three\n\n{big}\n"),
+        )
+        .unwrap();
+
+        let docs = backend(dir.path())
+            .fetch_library_documentation("/org/internal-lib", Some(2600), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(docs.contains("### One"));
+        assert!(docs.contains("### Two"));
+        assert!(!docs.contains("### Three"));
+    }
+
+    #[tokio::test]
+    async fn test_local_fetch_unknown_library_without_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        write_docs(dir.path());
+
+        let error = backend(dir.path())
+            .fetch_library_documentation("/vercel/next.js", None, None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Context7Error::NotFound));
+    }
+}
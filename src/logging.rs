@@ -0,0 +1,248 @@
+use rmcp::RoleServer;
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::serde_json::Value;
+use rmcp::service::Peer;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Metadata, Span, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::{FilterFn, Filtered, filter_fn};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Only events from this crate are forwarded; dependencies such as the MCP
+/// transport log every message they send, which would feed back into itself.
+const FORWARDED_TARGET: &str = "c67_mcp";
+
+/// Span field naming the session whose request an event was recorded for.
+const SESSION_FIELD: &str = "mcp_session";
+
+/// Source of session IDs, unique within the process.
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+
+/// Forwards `tracing` events to MCP clients as `notifications/message`.
+///
+/// Install [`McpLogger::layer`] in the tracing subscriber and hand the logger to
+/// `Context7Tool::with_logger`; each session then receives events once its client
+/// sends `logging/setLevel`. Events reach only the session inside whose
+/// [`SessionLogger::span`] they were recorded; all others are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct McpLogger {
+    sinks: Arc<Mutex<Vec<Weak<LogSink>>>>,
+}
+
+impl McpLogger {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tracing layer feeding this logger.
+    ///
+    /// The layer filters per callsite, so it never enables logging in dependencies.
+    #[must_use]
+    pub fn layer<S>(&self) -> Filtered<McpLogLayer, FilterFn, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        McpLogLayer {
+            logger: self.clone(),
+        }
+        .with_filter(filter_fn(is_forwarded))
+    }
+
+    /// Logging state for a new MCP session, which receives nothing until it sets a level.
+    #[must_use]
+    pub fn session(&self) -> SessionLogger {
+        SessionLogger {
+            logger: self.clone(),
+            id: NEXT_SESSION.fetch_add(1, Ordering::Relaxed),
+            sink: OnceLock::new(),
+        }
+    }
+
+    fn publish(&self, session: u64, level: LoggingLevel, logger: &str, data: &str) {
+        let Ok(mut sinks) = self.sinks.lock() else {
+            return;
+        };
+        sinks.retain(|sink| sink.upgrade().is_some_and(|sink| !sink.sender.is_closed()));
+        for sink in sinks.iter().filter_map(Weak::upgrade) {
+            if sink.session == session
+                && severity(level) >= sink.min_severity.load(Ordering::Relaxed)
+            {
+                let _ = sink.sender.send(LoggingMessageNotificationParam {
+                    level,
+                    logger: Some(logger.to_string()),
+                    data: Value::String(data.to_string()),
+                });
+            }
+        }
+    }
+}
+
+/// A session's subscription to log messages, alive as long as the session is.
+#[derive(Debug)]
+struct LogSink {
+    session: u64,
+    min_severity: AtomicU8,
+    sender: mpsc::UnboundedSender<LoggingMessageNotificationParam>,
+}
+
+/// Per-session logging: the level the client asked for and where messages go.
+#[derive(Debug)]
+pub struct SessionLogger {
+    logger: McpLogger,
+    id: u64,
+    sink: OnceLock<Arc<LogSink>>,
+}
+
+impl SessionLogger {
+    /// A span marking the events recorded inside it as this session's.
+    #[must_use]
+    pub fn span(&self) -> Span {
+        tracing::info_span!("mcp_session", mcp_session = self.id)
+    }
+
+    /// Sends events at `level` and above to `peer` from now on.
+    ///
+    /// The first call starts forwarding; later calls only change the level.
+    pub fn set_level(&self, peer: &Peer<RoleServer>, level: LoggingLevel) {
+        let sink = self.sink.get_or_init(|| {
+            let (sender, mut messages) = mpsc::unbounded_channel();
+            let peer = peer.clone();
+            tokio::spawn(async move {
+                while let Some(message) = messages.recv().await {
+                    if peer.notify_logging_message(message).await.is_err() {
+                        break;
+                    }
+                }
+            });
+            let sink = Arc::new(LogSink {
+                session: self.id,
+                min_severity: AtomicU8::new(severity(level)),
+                sender,
+            });
+            if let Ok(mut sinks) = self.logger.sinks.lock() {
+                sinks.push(Arc::downgrade(&sink));
+            }
+            sink
+        });
+        sink.min_severity.store(severity(level), Ordering::Relaxed);
+    }
+}
+
+/// Tracing layer created by [`McpLogger::layer`].
+#[derive(Debug, Clone)]
+pub struct McpLogLayer {
+    logger: McpLogger,
+}
+
+/// The session a span belongs to, kept in its extensions.
+struct SessionId(u64);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for McpLogLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = SessionVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(session), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SessionId(session));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let Some(session) = ctx.event_scope(event).and_then(|mut scope| {
+            scope.find_map(|span| span.extensions().get::<SessionId>().map(|id| id.0))
+        }) else {
+            return;
+        };
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        self.logger.publish(
+            session,
+            logging_level(*metadata.level()),
+            metadata.target(),
+            &message.text(),
+        );
+    }
+}
+
+/// Whether spans and events from a callsite can reach MCP clients.
+fn is_forwarded(metadata: &Metadata<'_>) -> bool {
+    metadata.target().starts_with(FORWARDED_TARGET)
+}
+
+/// The MCP level a tracing level is reported at.
+#[must_use]
+pub fn logging_level(level: Level) -> LoggingLevel {
+    match level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        _ => LoggingLevel::Debug,
+    }
+}
+
+/// Orders MCP levels from least to most severe.
+const fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// Renders an event as its message followed by any other fields as `name=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl MessageVisitor {
+    fn text(mut self) -> String {
+        self.fields.insert(0, self.message);
+        self.fields.retain(|part| !part.is_empty());
+        self.fields.join(" ")
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={value}", field.name()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.push(format!("{}={value:?}", field.name()));
+        }
+    }
+}
+
+/// Picks the session ID out of a span's fields.
+struct SessionVisitor(Option<u64>);
+
+impl Visit for SessionVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == SESSION_FIELD {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
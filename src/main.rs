@@ -1,10 +1,53 @@
-mod client;
-mod formatting;
-mod server;
+use anyhow::{Result, bail};
+use c67_mcp::{
+    AllowedOrigins, CacheConfig, Context7Client, Context7Tool, DocsBackend, DocsCache,
+    FederatedBackend, HttpConfig, LocalDocsBackend, LocalSource, McpLogger, ResponseLimit,
+    RetryPolicy, RustdocBackend, TokenCounter, Transport, run_server,
+};
+use clap::{Parser, ValueEnum};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
-use anyhow::Result;
-use clap::Parser;
-use server::run_server;
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8080";
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TransportKind {
+    /// Serve a single client over stdin/stdout
+    Stdio,
+    /// Serve many clients over MCP Streamable HTTP
+    Http,
+    /// Serve older clients over the legacy HTTP+SSE transport
+    Sse,
+}
+
+/// Value of `--listen`: a TCP socket address or `unix:<path>`.
+#[derive(Clone, Debug)]
+enum ListenAddr {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err("unix socket path must not be empty".to_string());
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        s.parse()
+            .map(Self::Tcp)
+            .map_err(|e| format!("expected HOST:PORT or unix:PATH ({e})"))
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "c67-mcp")]
@@ -30,18 +73,190 @@ struct Cli {
     /// Disable TLS certificate verification (insecure, for corporate MITM)
     #[arg(long)]
     insecure: bool,
+
+    /// Transport used to talk to MCP clients
+    #[arg(long, value_enum, default_value = "stdio")]
+    transport: TransportKind,
+
+    /// Address to listen on: HOST:PORT for http/sse (default 127.0.0.1:8080),
+    /// or unix:PATH to serve stdio framing on a Unix socket
+    #[arg(long)]
+    listen: Option<ListenAddr>,
+
+    /// Browser origin, besides localhost, allowed to use the HTTP transports (repeatable)
+    #[arg(long, value_name = "ORIGIN", value_parser = AllowedOrigins::parse_origin)]
+    allowed_origin: Vec<String>,
+
+    /// Directory for the documentation cache (default: $XDG_CACHE_HOME/c67-mcp)
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Seconds before cached documentation is fetched again
+    #[arg(long, default_value_t = 86400)]
+    cache_ttl: u64,
+
+    /// Disable the on-disk documentation cache
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Answer only from the documentation cache, never contacting Context7
+    #[arg(long, conflicts_with = "no_cache")]
+    offline: bool,
+
+    /// Retries for rate-limited, failed or dropped Context7 requests
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Seconds a single search or fetch may take, retries included
+    #[arg(long, default_value_t = 30)]
+    request_timeout: u64,
+
+    /// Idle connections kept open to Context7 for reuse
+    #[arg(long, default_value_t = 4)]
+    pool_size: usize,
+
+    /// Seconds an idle pooled connection is kept open
+    #[arg(long, default_value_t = 60)]
+    idle_timeout: u64,
+
+    /// Seconds to wait for a connection to Context7
+    #[arg(long, default_value_t = 10)]
+    connect_timeout: u64,
+
+    /// Seconds to wait for Context7 to respond
+    #[arg(long, default_value_t = 30)]
+    read_timeout: u64,
+
+    /// Serve a local directory of Markdown/text docs as /org/project=DIR (repeatable)
+    #[arg(long, value_name = "ID=DIR", value_parser = LocalSource::parse)]
+    local_docs: Vec<LocalSource>,
+
+    /// Serve rustdoc JSON files in this directory (e.g. target/doc) as /crates/<name> (repeatable)
+    #[arg(long, value_name = "DIR")]
+    rustdoc_dir: Vec<PathBuf>,
+
+    /// Also search a self-hosted Context7-compatible API at this base URL (repeatable)
+    #[arg(long, value_name = "URL")]
+    mirror: Vec<String>,
+
+    /// Seconds between checks of subscribed resources for changed documentation
+    #[arg(long, default_value_t = 900, value_parser = clap::value_parser!(u64).range(1..))]
+    refresh_interval: u64,
+
+    /// Truncate documentation longer than this many tokens before returning it
+    #[arg(long, default_value_t = 50_000, value_parser = clap::value_parser!(u64).range(1..))]
+    max_response_tokens: u64,
+
+    /// How tokens are counted locally: 'bytes' (about 4 per token) or 'words'
+    #[arg(long, default_value = "bytes", value_parser = TokenCounter::parse)]
+    tokenizer: TokenCounter,
+}
+
+fn resolve_transport(kind: TransportKind, listen: Option<ListenAddr>) -> Result<Transport> {
+    let tcp_addr = |listen: Option<ListenAddr>| match listen {
+        None => Ok(DEFAULT_LISTEN_ADDR.parse().expect("valid default address")),
+        Some(ListenAddr::Tcp(addr)) => Ok(addr),
+        Some(ListenAddr::Unix(_)) => bail!("HTTP transports can only listen on HOST:PORT"),
+    };
+
+    Ok(match (kind, listen) {
+        (TransportKind::Stdio, None) => Transport::Stdio,
+        #[cfg(unix)]
+        (TransportKind::Stdio, Some(ListenAddr::Unix(path))) => Transport::Unix(path),
+        #[cfg(not(unix))]
+        (TransportKind::Stdio, Some(ListenAddr::Unix(_))) => {
+            bail!("Unix sockets are not supported on this platform")
+        }
+        (TransportKind::Stdio, Some(ListenAddr::Tcp(_))) => {
+            bail!("--listen HOST:PORT requires --transport http or --transport sse")
+        }
+        (TransportKind::Http, listen) => Transport::StreamableHttp(tcp_addr(listen)?),
+        (TransportKind::Sse, listen) => Transport::Sse(tcp_addr(listen)?),
+    })
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    if cli.debug || cli.verbose > 0 {
-        tracing_subscriber::fmt()
-            .with_env_filter(&cli.log_level)
+    // Events always reach MCP clients that enable logging; stderr only gets them
+    // when asked for.
+    let logger = McpLogger::new();
+    let stderr = (cli.debug || cli.verbose > 0).then(|| {
+        tracing_subscriber::fmt::layer()
             .with_writer(std::io::stderr)
-            .init();
+            .with_filter(EnvFilter::new(&cli.log_level))
+    });
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(logger.layer())
+        .init();
+
+    let transport = resolve_transport(cli.transport, cli.listen)?;
+
+    let retry = RetryPolicy {
+        max_retries: cli.max_retries,
+        deadline: Duration::from_secs(cli.request_timeout),
+        ..RetryPolicy::default()
+    };
+    let http = HttpConfig {
+        max_idle_connections: cli
+            .pool_size
+            .max(HttpConfig::default().max_idle_connections),
+        max_idle_connections_per_host: cli.pool_size,
+        idle_timeout: Duration::from_secs(cli.idle_timeout),
+        connect_timeout: Some(Duration::from_secs(cli.connect_timeout)),
+        read_timeout: Some(Duration::from_secs(cli.read_timeout)),
+    };
+    let mut client = Context7Client::new(cli.api_key, cli.insecure)
+        .with_http_config(&http)
+        .with_offline(cli.offline)
+        .with_retry_policy(retry.clone());
+    if !cli.no_cache
+        && let Some(dir) = cli.cache_dir.or_else(CacheConfig::default_dir)
+    {
+        let config = CacheConfig {
+            ttl: Duration::from_secs(cli.cache_ttl),
+            ..CacheConfig::new(dir)
+        };
+        client = client.with_cache(DocsCache::new(config));
     }
 
-    run_server(cli.api_key, cli.insecure).await
+    let client: Arc<dyn DocsBackend> = Arc::new(client);
+    let backend: Arc<dyn DocsBackend> =
+        if cli.local_docs.is_empty() && cli.rustdoc_dir.is_empty() && cli.mirror.is_empty() {
+            client
+        } else {
+            let mut federated = FederatedBackend::new();
+            if !cli.local_docs.is_empty() {
+                federated =
+                    federated.with_source("local", Arc::new(LocalDocsBackend::new(cli.local_docs)));
+            }
+            if !cli.rustdoc_dir.is_empty() {
+                federated = federated
+                    .with_source("rustdoc", Arc::new(RustdocBackend::new(cli.rustdoc_dir)));
+            }
+            federated = federated.with_source("context7", client);
+            if !cli.offline {
+                for mirror in cli.mirror {
+                    let mirror_client =
+                        Context7Client::new_with_base_url(None, mirror.clone(), cli.insecure)
+                            .with_http_config(&http)
+                            .with_retry_policy(retry.clone());
+                    federated =
+                        federated.with_source(format!("mirror {mirror}"), Arc::new(mirror_client));
+                }
+            }
+            Arc::new(federated)
+        };
+
+    let tool = Context7Tool::with_backend(backend)
+        .with_refresh_interval(Duration::from_secs(cli.refresh_interval))
+        .with_logger(logger)
+        .with_allowed_origins(AllowedOrigins::new(cli.allowed_origin))
+        .with_response_limit(ResponseLimit {
+            max_tokens: usize::try_from(cli.max_response_tokens).unwrap_or(usize::MAX),
+            counter: cli.tokenizer,
+        });
+    run_server(tool, transport).await
 }
@@ -0,0 +1,83 @@
+use std::net::IpAddr;
+
+/// Which browser origins and `Host` names the HTTP transports accept.
+///
+/// Loopback names are always accepted. Anything else must be listed, so a web
+/// page that points its own domain at 127.0.0.1 (DNS rebinding) cannot reach the
+/// server through the victim's browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedOrigins {
+    origins: Vec<String>,
+}
+
+impl AllowedOrigins {
+    /// Accepts loopback origins plus `origins`, each as returned by [`Self::parse_origin`].
+    #[must_use]
+    pub fn new(origins: Vec<String>) -> Self {
+        Self { origins }
+    }
+
+    /// Parses `scheme://host[:port]` as given to `--allowed-origin`.
+    pub fn parse_origin(origin: &str) -> Result<String, String> {
+        let normalized = origin.trim_end_matches('/').to_lowercase();
+        let valid = normalized
+            .split_once("://")
+            .is_some_and(|(scheme, authority)| {
+                !scheme.is_empty()
+                    && !hostname(authority).is_empty()
+                    && !authority.contains(['/', '?', '#'])
+            });
+        if !valid {
+            return Err(format!(
+                "expected an origin like https://example.com, got '{origin}'"
+            ));
+        }
+        Ok(normalized)
+    }
+
+    /// Whether a request carrying these `Origin` and `Host` headers may be served.
+    ///
+    /// Missing headers are accepted: non-browser clients send no `Origin`.
+    #[must_use]
+    pub fn allows(&self, origin: Option<&str>, host: Option<&str>) -> bool {
+        origin.is_none_or(|origin| self.allows_origin(origin))
+            && host.is_none_or(|host| self.allows_host(host))
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_lowercase();
+        match origin.split_once("://") {
+            Some(("http" | "https", authority)) if is_loopback(hostname(authority)) => true,
+            _ => self.origins.contains(&origin),
+        }
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        let host = hostname(host).to_lowercase();
+        is_loopback(&host)
+            || self.origins.iter().any(|origin| {
+                origin
+                    .split_once("://")
+                    .is_some_and(|(_, authority)| hostname(authority) == host)
+            })
+    }
+}
+
+/// The host part of `host[:port]`, keeping the brackets of an IPv6 address.
+fn hostname(authority: &str) -> &str {
+    if authority.starts_with('[') {
+        return authority
+            .find(']')
+            .map_or(authority, |end| &authority[..=end]);
+    }
+    authority.split(':').next().unwrap_or_default()
+}
+
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use crate::AllowedOrigins;
+
+    #[test]
+    fn test_parse_origin() {
+        assert_eq!(
+            AllowedOrigins::parse_origin("https://App.Example.com/"),
+            Ok("https://app.example.com".to_string())
+        );
+        assert!(AllowedOrigins::parse_origin("http://docs-host:8080").is_ok());
+        assert!(AllowedOrigins::parse_origin("app.example.com").is_err());
+        assert!(AllowedOrigins::parse_origin("https://app.example.com/path").is_err());
+        assert!(AllowedOrigins::parse_origin("https://").is_err());
+    }
+
+    #[test]
+    fn test_loopback_is_always_allowed() {
+        let allowed = AllowedOrigins::default();
+
+        assert!(allowed.allows(None, None));
+        assert!(allowed.allows(Some("http://localhost:3000"), Some("localhost:8080")));
+        assert!(allowed.allows(Some("https://127.0.0.1"), Some("127.0.0.1:8080")));
+        assert!(allowed.allows(Some("http://[::1]:3000"), Some("[::1]:8080")));
+        assert!(allowed.allows(None, Some("LOCALHOST")));
+    }
+
+    #[test]
+    fn test_rebound_hosts_and_foreign_origins_are_rejected() {
+        let allowed = AllowedOrigins::default();
+
+        assert!(!allowed.allows(None, Some("attacker.example:8080")));
+        assert!(!allowed.allows(Some("https://attacker.example"), Some("127.0.0.1:8080")));
+        assert!(!allowed.allows(Some("null"), Some("127.0.0.1:8080")));
+        assert!(!allowed.allows(Some("http://localhost.attacker.example"), None));
+        assert!(!allowed.allows(None, Some("")));
+    }
+
+    #[test]
+    fn test_listed_origins_are_allowed() {
+        let allowed = AllowedOrigins::new(vec![
+            AllowedOrigins::parse_origin("http://docs-host:8080").unwrap(),
+        ]);
+
+        assert!(allowed.allows(Some("http://docs-host:8080"), Some("docs-host:8080")));
+        assert!(allowed.allows(None, Some("docs-host")));
+        assert!(!allowed.allows(Some("https://docs-host:8080"), Some("docs-host:8080")));
+        assert!(!allowed.allows(None, Some("other-host:8080")));
+    }
+}
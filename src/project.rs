@@ -0,0 +1,718 @@
+use futures::StreamExt;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backend::DocsBackend;
+use crate::client::SearchResult;
+use crate::error::Context7Error;
+use crate::resources::percent_decode;
+
+/// How many dependency lookups run against the backend at once.
+const RESOLVE_CONCURRENCY: usize = 4;
+
+/// Most dependencies looked up per project, so a large workspace cannot use up
+/// the rate limit that every other tool shares.
+pub const MAX_RESOLVED_DEPENDENCIES: usize = 50;
+
+/// How long looking up one project's dependencies may take in total.
+pub const RESOLVE_DEADLINE: Duration = Duration::from_secs(60);
+
+/// The package ecosystem a dependency was declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    Rust,
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Rust => "Rust",
+            Self::JavaScript => "JavaScript",
+            Self::Python => "Python",
+            Self::Go => "Go",
+        })
+    }
+}
+
+/// A direct dependency declared in a project manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectDependency {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    /// The version requirement as written in the manifest.
+    pub requirement: Option<String>,
+    /// The exact version pinned by the lockfile (or the manifest, for go.mod).
+    pub locked_version: Option<String>,
+    /// Manifest the dependency was found in, relative to its project root.
+    pub manifest: String,
+}
+
+/// A dependency together with the library ID it resolved to.
+#[derive(Debug)]
+pub struct ProjectLibrary {
+    pub dependency: ProjectDependency,
+    /// `Ok(None)` when no search result matched the dependency's name; `None`
+    /// when the dependency was not looked up because of the lookup limit or deadline.
+    pub resolution: Option<Result<Option<String>, Context7Error>>,
+}
+
+/// Reads the manifests and lockfiles in `root` and lists their direct dependencies.
+///
+/// Understands Cargo.toml/Cargo.lock (including explicit workspace members),
+/// package.json with package-lock.json, yarn.lock or pnpm-lock.yaml,
+/// pyproject.toml/requirements.txt with poetry.lock or uv.lock, and go.mod.
+#[must_use]
+pub fn discover_dependencies(root: &Path) -> Vec<ProjectDependency> {
+    let mut dependencies = Vec::new();
+    dependencies.extend(cargo_dependencies(root));
+    dependencies.extend(npm_dependencies(root));
+    dependencies.extend(python_dependencies(root));
+    dependencies.extend(go_dependencies(root));
+
+    let mut seen = HashSet::new();
+    dependencies.retain(|dep| seen.insert((dep.ecosystem, dep.name.clone())));
+    dependencies
+}
+
+/// Looks each dependency up through `backend`, a few at a time, keeping their order.
+///
+/// Only the first [`MAX_RESOLVED_DEPENDENCIES`] are looked up, and lookups stop
+/// when `deadline` passes; the remaining dependencies are returned unresolved.
+pub async fn resolve_dependencies(
+    backend: &dyn DocsBackend,
+    dependencies: Vec<ProjectDependency>,
+    deadline: Duration,
+) -> Vec<ProjectLibrary> {
+    let mut resolutions = Vec::new();
+    let lookups =
+        futures::stream::iter(dependencies.iter().take(MAX_RESOLVED_DEPENDENCIES).cloned())
+            .map(|dependency| async move {
+                backend
+                    .search_libraries(search_term(&dependency))
+                    .await
+                    .map(|response| best_match(&dependency, &response.results))
+            })
+            .buffered(RESOLVE_CONCURRENCY)
+            .for_each(|resolution| {
+                resolutions.push(resolution);
+                std::future::ready(())
+            });
+    if tokio::time::timeout(deadline, lookups).await.is_err() {
+        tracing::warn!("Dependency lookups did not finish within {deadline:?}");
+    }
+    if dependencies.len() > resolutions.len() {
+        tracing::info!(
+            "Looked up {} of {} dependencies",
+            resolutions.len(),
+            dependencies.len()
+        );
+    }
+
+    let mut resolutions = resolutions.into_iter();
+    dependencies
+        .into_iter()
+        .map(|dependency| ProjectLibrary {
+            dependency,
+            resolution: resolutions.next(),
+        })
+        .collect()
+}
+
+/// The name to search Context7 for: Go modules are known by their last path segment.
+fn search_term(dependency: &ProjectDependency) -> &str {
+    if dependency.ecosystem != Ecosystem::Go {
+        return &dependency.name;
+    }
+    let mut segments = dependency.name.rsplit('/');
+    let last = segments.next().unwrap_or(&dependency.name);
+    // `github.com/org/repo/v2` is still `repo`.
+    if last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit()) {
+        segments.next().unwrap_or(last)
+    } else {
+        last
+    }
+}
+
+/// Picks the result naming the dependency, preferring a version-pinned ID.
+fn best_match(dependency: &ProjectDependency, results: &[SearchResult]) -> Option<String> {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let full_name = normalize(&dependency.name);
+    let short_name = normalize(
+        search_term(dependency)
+            .rsplit('/')
+            .next()
+            .unwrap_or_default(),
+    );
+
+    // A GitHub-hosted Go module names its repository exactly.
+    let github_id = dependency
+        .name
+        .strip_prefix("github.com/")
+        .map(|path| format!("/{}", path.split('/').take(2).collect::<Vec<_>>().join("/")));
+
+    let result = results
+        .iter()
+        .find(|r| {
+            github_id
+                .as_deref()
+                .is_some_and(|id| r.id.eq_ignore_ascii_case(id))
+        })
+        .or_else(|| {
+            results.iter().find(|r| {
+                let title = normalize(&r.title);
+                let project = normalize(r.id.trim_matches('/').split('/').nth(1).unwrap_or(""));
+                [&full_name, &short_name]
+                    .iter()
+                    .any(|name| **name == title || **name == project)
+            })
+        })?;
+
+    let versioned = dependency.locked_version.as_deref().and_then(|locked| {
+        let locked = locked.trim_start_matches('v');
+        result
+            .versions
+            .as_ref()?
+            .iter()
+            .find(|v| v.trim_start_matches('v') == locked)
+    });
+    Some(versioned.map_or_else(
+        || result.id.clone(),
+        |version| format!("{}/{version}", result.id.trim_end_matches('/')),
+    ))
+}
+
+fn dependency(
+    name: &str,
+    ecosystem: Ecosystem,
+    requirement: Option<String>,
+    locked_version: Option<String>,
+    manifest: &str,
+) -> ProjectDependency {
+    ProjectDependency {
+        name: name.to_string(),
+        ecosystem,
+        requirement,
+        locked_version,
+        manifest: manifest.to_string(),
+    }
+}
+
+fn read(root: &Path, file: &str) -> Option<String> {
+    fs::read_to_string(root.join(file)).ok()
+}
+
+fn cargo_dependencies(root: &Path) -> Vec<ProjectDependency> {
+    let Some(manifest) =
+        read(root, "Cargo.toml").and_then(|text| parse_toml::<CargoManifest>(&text))
+    else {
+        return Vec::new();
+    };
+    let locked = read(root, "Cargo.lock")
+        .map(|lock| lock_packages(&lock, |name| name.to_string()))
+        .unwrap_or_default();
+
+    let mut members = Vec::new();
+    for member in &manifest.workspace.members {
+        for dir in expand_member(root, member) {
+            let path = dir.join("Cargo.toml");
+            if let Some(member) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| parse_toml::<CargoManifest>(&text))
+            {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                members.push((relative.to_string_lossy().replace('\\', "/"), member));
+            }
+        }
+    }
+    let workspace = &manifest.workspace.dependencies;
+
+    // Shared workspace dependencies come last, so that a member using one is
+    // reported as the manifest declaring it.
+    let declared = std::iter::once(("Cargo.toml", &manifest))
+        .chain(members.iter().map(|(file, member)| (file.as_str(), member)))
+        .flat_map(|(file, manifest)| {
+            manifest
+                .dependency_tables()
+                .flatten()
+                .map(move |entry| (file, entry))
+        })
+        .chain(workspace.iter().map(|entry| ("Cargo.toml", entry)));
+
+    let mut dependencies = Vec::new();
+    for (file, (key, declaration)) in declared {
+        // `serde.workspace = true` takes its details from `[workspace.dependencies]`.
+        let declaration = match declaration {
+            TomlDependency::Detailed(detail) if detail.workspace => {
+                workspace.get(key).unwrap_or(declaration)
+            }
+            declaration => declaration,
+        };
+        push_cargo_dependency(&mut dependencies, key, declaration, &locked, file);
+    }
+    dependencies
+}
+
+fn push_cargo_dependency(
+    dependencies: &mut Vec<ProjectDependency>,
+    key: &str,
+    declared: &TomlDependency,
+    locked: &HashMap<String, String>,
+    manifest: &str,
+) {
+    let (name, requirement) = match declared {
+        TomlDependency::Version(version) => (key, Some(version.clone())),
+        TomlDependency::Detailed(detail) => {
+            // Path-only dependencies are the project's own crates.
+            if detail.path.is_some() && detail.version.is_none() {
+                return;
+            }
+            (
+                detail.package.as_deref().unwrap_or(key),
+                detail.version.clone(),
+            )
+        }
+        TomlDependency::Other(_) => (key, None),
+    };
+    let locked_version = locked.get(name).cloned();
+    dependencies.push(dependency(
+        name,
+        Ecosystem::Rust,
+        requirement,
+        locked_version,
+        manifest,
+    ));
+}
+
+/// Expands a workspace member, supporting a trailing `/*` glob.
+fn expand_member(root: &Path, member: &str) -> Vec<PathBuf> {
+    match member.strip_suffix("/*") {
+        Some(parent) => fs::read_dir(root.join(parent))
+            .map(|entries| {
+                let mut dirs: Vec<PathBuf> = entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir())
+                    .collect();
+                dirs.sort();
+                dirs
+            })
+            .unwrap_or_default(),
+        None => vec![root.join(member)],
+    }
+}
+
+/// Reads `[[package]]` name/version pairs from Cargo.lock, poetry.lock or uv.lock.
+///
+/// When a package is locked at several versions the last (highest) one wins.
+fn lock_packages(lock: &str, normalize: impl Fn(&str) -> String) -> HashMap<String, String> {
+    parse_toml::<Lockfile>(lock)
+        .unwrap_or_default()
+        .package
+        .into_iter()
+        .filter_map(|package| Some((normalize(&package.name), package.version?)))
+        .collect()
+}
+
+fn npm_dependencies(root: &Path) -> Vec<ProjectDependency> {
+    let Some(manifest) = read(root, "package.json")
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+    else {
+        return Vec::new();
+    };
+
+    let locked = npm_locked_versions(root);
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|field| manifest.get(field)?.as_object())
+        .flatten()
+        .map(|(name, requirement)| {
+            dependency(
+                name,
+                Ecosystem::JavaScript,
+                requirement.as_str().map(ToString::to_string),
+                locked.get(name).cloned(),
+                "package.json",
+            )
+        })
+        .collect()
+}
+
+fn npm_locked_versions(root: &Path) -> HashMap<String, String> {
+    if let Some(lock) = read(root, "package-lock.json")
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+    {
+        let version = |entry: &serde_json::Value| entry.get("version")?.as_str().map(String::from);
+        // lockfileVersion 2+ keys installed packages by path; version 1 by name.
+        if let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) {
+            return packages
+                .iter()
+                .filter_map(|(path, entry)| {
+                    let name = path.strip_prefix("node_modules/")?;
+                    (!name.contains("/node_modules/")).then_some(())?;
+                    Some((name.to_string(), version(entry)?))
+                })
+                .collect();
+        }
+        if let Some(dependencies) = lock.get("dependencies").and_then(|d| d.as_object()) {
+            return dependencies
+                .iter()
+                .filter_map(|(name, entry)| Some((name.clone(), version(entry)?)))
+                .collect();
+        }
+    }
+    if let Some(lock) = read(root, "yarn.lock") {
+        return yarn_locked_versions(&lock);
+    }
+    if let Some(lock) = read(root, "pnpm-lock.yaml") {
+        return pnpm_locked_versions(&lock);
+    }
+    HashMap::new()
+}
+
+/// Parses both classic (`version "1.0.0"`) and Berry (`version: 1.0.0`) yarn.lock files.
+fn yarn_locked_versions(lock: &str) -> HashMap<String, String> {
+    let mut locked = HashMap::new();
+    let mut names = Vec::new();
+    for line in lock.lines() {
+        if !line.starts_with(' ') && line.ends_with(':') && !line.starts_with('#') {
+            names = line
+                .trim_end_matches(':')
+                .split(", ")
+                .filter_map(|spec| split_package_spec(spec.trim_matches('"')))
+                .map(|(name, _)| name.to_string())
+                .collect();
+        } else if let Some(version) = line
+            .trim()
+            .strip_prefix("version")
+            .map(|v| v.trim_start_matches(':').trim().trim_matches('"'))
+        {
+            for name in names.drain(..) {
+                locked.insert(name, version.to_string());
+            }
+        }
+    }
+    locked
+}
+
+/// Reads the keys of the `packages:` map, e.g. `/react@18.2.0:` or `react@18.2.0(peer):`.
+fn pnpm_locked_versions(lock: &str) -> HashMap<String, String> {
+    let mut locked = HashMap::new();
+    let mut in_packages = false;
+    for line in lock.lines().filter(|line| !line.trim().is_empty()) {
+        if !line.starts_with(' ') {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if !in_packages || line.starts_with("    ") || !line.trim_end().ends_with(':') {
+            continue;
+        }
+        let key = line
+            .trim()
+            .trim_end_matches(':')
+            .trim_matches(|c| c == '\'' || c == '"');
+        let key = key.strip_prefix('/').unwrap_or(key);
+        if let Some((name, version)) = split_package_spec(key) {
+            let version = version.split('(').next().unwrap_or(version);
+            locked.insert(name.to_string(), version.to_string());
+        }
+    }
+    locked
+}
+
+/// Splits `name@version`, keeping the `@` of a scoped package name.
+fn split_package_spec(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    let (name, version) = (&spec[..at], &spec[at + 1..]);
+    let version = version.strip_prefix("npm:").unwrap_or(version);
+    Some((name, version))
+}
+
+fn python_dependencies(root: &Path) -> Vec<ProjectDependency> {
+    let locked = read(root, "poetry.lock")
+        .or_else(|| read(root, "uv.lock"))
+        .map(|lock| lock_packages(&lock, normalize_python_name))
+        .unwrap_or_default();
+    let mut dependencies = Vec::new();
+    let mut push = |name: &str, requirement: Option<String>, manifest: &str| {
+        let pinned = requirement
+            .as_deref()
+            .and_then(|r| r.strip_prefix("=="))
+            .filter(|v| !v.contains([',', '*']))
+            .map(|v| v.trim().to_string());
+        let locked_version = locked.get(&normalize_python_name(name)).cloned().or(pinned);
+        dependencies.push(dependency(
+            name,
+            Ecosystem::Python,
+            requirement,
+            locked_version,
+            manifest,
+        ));
+    };
+
+    if let Some(manifest) =
+        read(root, "pyproject.toml").and_then(|text| parse_toml::<PyProject>(&text))
+    {
+        let requirements = manifest
+            .project
+            .dependencies
+            .iter()
+            .chain(manifest.project.optional_dependencies.values().flatten())
+            .map(String::as_str)
+            .chain(
+                manifest
+                    .dependency_groups
+                    .values()
+                    .flatten()
+                    .filter_map(toml::Value::as_str),
+            );
+        for requirement in requirements {
+            if let Some((name, spec)) = parse_pep508(requirement) {
+                push(name, spec, "pyproject.toml");
+            }
+        }
+
+        let poetry = &manifest.tool.poetry;
+        let poetry_dependencies = [&poetry.dependencies, &poetry.dev_dependencies]
+            .into_iter()
+            .chain(poetry.group.values().map(|group| &group.dependencies))
+            .flatten();
+        for (name, declared) in poetry_dependencies {
+            if name == "python" {
+                continue;
+            }
+            let requirement = match declared {
+                TomlDependency::Version(version) => Some(version.clone()),
+                TomlDependency::Detailed(detail) => detail.version.clone(),
+                TomlDependency::Other(_) => None,
+            };
+            push(name, requirement, "pyproject.toml");
+        }
+    }
+
+    if let Some(requirements) = read(root, "requirements.txt") {
+        for line in requirements.lines() {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with(['#', '-']) || line.contains("://") {
+                continue;
+            }
+            if let Some((name, spec)) = parse_pep508(line) {
+                push(name, spec, "requirements.txt");
+            }
+        }
+    }
+    dependencies
+}
+
+/// Splits a PEP 508 requirement like `requests[socks]>=2.31; python_version > "3.8"`.
+fn parse_pep508(requirement: &str) -> Option<(&str, Option<String>)> {
+    let requirement = requirement.split(';').next()?.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let rest = requirement[end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, rest)| rest),
+        None => rest,
+    };
+    let spec = rest.trim().trim_matches(|c| c == '(' || c == ')').trim();
+    Some((name, (!spec.is_empty()).then(|| spec.replace(' ', ""))))
+}
+
+/// PEP 503 normalisation: case-insensitive, with runs of `-_.` equivalent.
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+fn go_dependencies(root: &Path) -> Vec<ProjectDependency> {
+    let Some(manifest) = read(root, "go.mod") else {
+        return Vec::new();
+    };
+    let mut dependencies = Vec::new();
+    let mut in_require = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        let spec = if in_require {
+            if line == ")" {
+                in_require = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_require = true;
+            continue;
+        } else if let Some(spec) = line.strip_prefix("require ") {
+            spec
+        } else {
+            continue;
+        };
+        // Indirect requirements are dependencies of dependencies.
+        if spec.ends_with("// indirect") {
+            continue;
+        }
+        let mut parts = spec.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            dependencies.push(dependency(
+                module,
+                Ecosystem::Go,
+                Some(version.to_string()),
+                Some(version.to_string()),
+                "go.mod",
+            ));
+        }
+    }
+    dependencies
+}
+
+/// Parses a TOML document, treating malformed files as absent.
+fn parse_toml<T: DeserializeOwned>(text: &str) -> Option<T> {
+    toml::from_str(text)
+        .map_err(|e| tracing::debug!("Skipping malformed TOML: {e}"))
+        .ok()
+}
+
+/// The parts of a Cargo.toml that declare dependencies.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct CargoManifest {
+    workspace: CargoWorkspace,
+    dependencies: IndexMap<String, TomlDependency>,
+    dev_dependencies: IndexMap<String, TomlDependency>,
+    build_dependencies: IndexMap<String, TomlDependency>,
+    target: IndexMap<String, CargoTarget>,
+}
+
+impl CargoManifest {
+    /// Every dependency table of the package, including platform-specific ones.
+    fn dependency_tables(&self) -> impl Iterator<Item = &IndexMap<String, TomlDependency>> {
+        [
+            &self.dependencies,
+            &self.dev_dependencies,
+            &self.build_dependencies,
+        ]
+        .into_iter()
+        .chain(self.target.values().flat_map(|target| {
+            [
+                &target.dependencies,
+                &target.dev_dependencies,
+                &target.build_dependencies,
+            ]
+        }))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CargoWorkspace {
+    members: Vec<String>,
+    dependencies: IndexMap<String, TomlDependency>,
+}
+
+/// A `[target.'cfg(..)']` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct CargoTarget {
+    dependencies: IndexMap<String, TomlDependency>,
+    dev_dependencies: IndexMap<String, TomlDependency>,
+    build_dependencies: IndexMap<String, TomlDependency>,
+}
+
+/// A dependency declared in Cargo.toml or `[tool.poetry]`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TomlDependency {
+    /// `name = "1.0"`
+    Version(String),
+    /// `name = { version = "1.0", ... }` or `name.workspace = true`
+    Detailed(DependencyDetail),
+    /// Anything else, such as Poetry's lists of per-platform constraints.
+    Other(IgnoredAny),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DependencyDetail {
+    version: Option<String>,
+    package: Option<String>,
+    path: Option<String>,
+    workspace: bool,
+}
+
+/// `[[package]]` entries of Cargo.lock, poetry.lock or uv.lock.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Lockfile {
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: Option<String>,
+}
+
+/// The parts of a pyproject.toml that declare dependencies.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct PyProject {
+    project: PyProjectTable,
+    /// PEP 735 groups, whose entries are requirements or `{ include-group = ".." }`.
+    dependency_groups: IndexMap<String, Vec<toml::Value>>,
+    tool: PyProjectTools,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct PyProjectTable {
+    dependencies: Vec<String>,
+    optional_dependencies: IndexMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PyProjectTools {
+    poetry: Poetry,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Poetry {
+    dependencies: IndexMap<String, TomlDependency>,
+    dev_dependencies: IndexMap<String, TomlDependency>,
+    group: IndexMap<String, PoetryGroup>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PoetryGroup {
+    dependencies: IndexMap<String, TomlDependency>,
+}
+
+/// Converts a `file://` root URI from the client into a local path.
+#[must_use]
+pub fn path_from_file_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    let path = percent_decode(path)?;
+    // `file:///C:/dir` on Windows.
+    #[cfg(windows)]
+    let path = path.strip_prefix('/').unwrap_or(&path).to_string();
+    Some(PathBuf::from(path))
+}
@@ -0,0 +1,410 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Context7Error, DocsBackend, Ecosystem, MAX_RESOLVED_DEPENDENCIES, ProjectDependency,
+        SearchResponse, SearchResult, discover_dependencies, path_from_file_uri,
+        resolve_dependencies,
+    };
+    use async_trait::async_trait;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn locked(root: &Path, ecosystem: Ecosystem, name: &str) -> Option<String> {
+        discover_dependencies(root)
+            .into_iter()
+            .find(|dep| dep.ecosystem == ecosystem && dep.name == name)
+            .unwrap_or_else(|| panic!("{name} not discovered"))
+            .locked_version
+    }
+
+    #[test]
+    fn test_discovers_cargo_dependencies_with_locked_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[workspace]
+members = ["crates/*"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] } # comment
+tokio = "1"
+json = { package = "serde_json", version = "1" }
+helper = { path = "crates/helper" }
+
+[dependencies.clap]
+version = "4"
+
+[dev-dependencies]
+wiremock = "0.6"
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("crates/helper")).unwrap();
+        fs::write(
+            dir.path().join("crates/helper/Cargo.toml"),
+            "[package]\nname = \"helper\"\n\n[dependencies]\nregex = \"1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Cargo.lock"),
+            r#"
+version = 4
+
+[[package]]
+name = "serde"
+version = "1.0.219"
+
+[[package]]
+name = "serde_json"
+version = "1.0.140"
+
+[[package]]
+name = "tokio"
+version = "1.47.1"
+"#,
+        )
+        .unwrap();
+
+        let deps = discover_dependencies(dir.path());
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["serde", "tokio", "serde_json", "clap", "wiremock", "regex"]
+        );
+        assert_eq!(deps[0].requirement.as_deref(), Some("1.0"));
+        assert_eq!(deps[0].locked_version.as_deref(), Some("1.0.219"));
+        assert_eq!(deps[2].locked_version.as_deref(), Some("1.0.140"));
+        assert_eq!(deps[3].requirement.as_deref(), Some("4"));
+        assert_eq!(deps[3].locked_version, None);
+        assert_eq!(deps[5].manifest, "crates/helper/Cargo.toml");
+    }
+
+    #[test]
+    fn test_inherits_workspace_dependencies_declared_with_dotted_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = [
+    "app",  # multi-line arrays may carry comments
+]
+
+[workspace.dependencies]
+serde = { version = "1.0.200", features = ["derive"] }
+json = { package = "serde_json", version = "1" }
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("app")).unwrap();
+        fs::write(
+            dir.path().join("app/Cargo.toml"),
+            r#"
+[package]
+name = "app"
+description = """
+A multi-line string.
+[dependencies]
+not-a-dependency = "1"
+"""
+
+[dependencies]
+serde.workspace = true
+json = { workspace = true }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+        )
+        .unwrap();
+
+        let deps = discover_dependencies(dir.path());
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["serde", "serde_json", "libc"]);
+        assert_eq!(deps[0].requirement.as_deref(), Some("1.0.200"));
+        assert_eq!(deps[0].manifest, "app/Cargo.toml");
+        assert_eq!(deps[1].requirement.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_discovers_npm_dependencies_from_each_lockfile_format() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18.2.0", "@tanstack/react-query": "^5.0.0"},
+                "devDependencies": {"vitest": "^1.0.0"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {
+                "": {"name": "app"},
+                "node_modules/react": {"version": "18.3.1"},
+                "node_modules/@tanstack/react-query": {"version": "5.51.1"},
+                "node_modules/vitest/node_modules/react": {"version": "17.0.0"}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "react").as_deref(),
+            Some("18.3.1")
+        );
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "@tanstack/react-query").as_deref(),
+            Some("5.51.1")
+        );
+        assert_eq!(locked(dir.path(), Ecosystem::JavaScript, "vitest"), None);
+
+        fs::remove_file(dir.path().join("package-lock.json")).unwrap();
+        fs::write(
+            dir.path().join("yarn.lock"),
+            "# yarn lockfile v1\n\nreact@^18.2.0, react@^18.0.0:\n  version \"18.2.0\"\n\n\"@tanstack/react-query@^5.0.0\":\n  version \"5.8.4\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "react").as_deref(),
+            Some("18.2.0")
+        );
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "@tanstack/react-query").as_deref(),
+            Some("5.8.4")
+        );
+
+        fs::remove_file(dir.path().join("yarn.lock")).unwrap();
+        fs::write(
+            dir.path().join("pnpm-lock.yaml"),
+            "lockfileVersion: '9.0'\n\npackages:\n\n  react@18.1.0:\n    resolution: {integrity: sha512-x}\n\n  '@tanstack/react-query@5.2.0(react@18.1.0)':\n    resolution: {integrity: sha512-y}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "react").as_deref(),
+            Some("18.1.0")
+        );
+        assert_eq!(
+            locked(dir.path(), Ecosystem::JavaScript, "@tanstack/react-query").as_deref(),
+            Some("5.2.0")
+        );
+    }
+
+    #[test]
+    fn test_discovers_python_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "svc"
+dependencies = [
+    "requests[socks]>=2.31",
+    "Flask_Login==0.6.3; python_version > '3.8'",
+]
+
+[project.optional-dependencies]
+test = ["pytest"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("requirements.txt"),
+            "# pinned\nnumpy==1.26.4\n-r other.txt\ngit+https://example.com/x.git\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("uv.lock"),
+            "[[package]]\nname = \"requests\"\nversion = \"2.32.3\"\n",
+        )
+        .unwrap();
+
+        let deps = discover_dependencies(dir.path());
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["requests", "Flask_Login", "pytest", "numpy"]);
+        assert_eq!(deps[0].requirement.as_deref(), Some(">=2.31"));
+        assert_eq!(deps[0].locked_version.as_deref(), Some("2.32.3"));
+        assert_eq!(deps[1].locked_version.as_deref(), Some("0.6.3"));
+        assert_eq!(deps[2].requirement, None);
+        assert_eq!(deps[3].locked_version.as_deref(), Some("1.26.4"));
+        assert_eq!(deps[3].manifest, "requirements.txt");
+    }
+
+    #[test]
+    fn test_discovers_direct_go_requirements() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/app\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgithub.com/gin-gonic/gin v1.9.1\n\tgolang.org/x/sys v0.15.0 // indirect\n)\n",
+        )
+        .unwrap();
+
+        let deps = discover_dependencies(dir.path());
+        let names: Vec<&str> = deps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["github.com/spf13/cobra", "github.com/gin-gonic/gin"]
+        );
+        assert_eq!(deps[1].locked_version.as_deref(), Some("v1.9.1"));
+    }
+
+    struct CatalogBackend;
+
+    #[async_trait]
+    impl DocsBackend for CatalogBackend {
+        async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+            let result = |id: &str, title: &str, versions: &[&str]| SearchResult {
+                id: id.to_string(),
+                title: title.to_string(),
+                description: String::new(),
+                total_snippets: None,
+                trust_score: None,
+                versions: Some(versions.iter().map(ToString::to_string).collect()),
+                origin: None,
+            };
+            let results = match query {
+                "gin" => vec![
+                    result("/gin-contrib/gin-docs", "Gin", &[]),
+                    result("/gin-gonic/gin", "Gin Web Framework", &["v1.9.1"]),
+                ],
+                "tokio" => vec![result("/tokio-rs/tokio", "Tokio", &["tokio-1.47.1"])],
+                "serde" => vec![result("/serde-rs/serde", "Serde", &["1.0.219"])],
+                "broken" => return Err(Context7Error::RateLimited { retry_after: None }),
+                "stalled" => std::future::pending().await,
+                _ => vec![result("/someone/unrelated", "Unrelated", &[])],
+            };
+            Ok(SearchResponse {
+                results,
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            _library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolves_dependencies_to_library_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[dependencies]\nserde = \"1\"\ntokio = \"1\"\nobscure = \"0.1\"\nbroken = \"1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Cargo.lock"),
+            "[[package]]\nname = \"serde\"\nversion = \"1.0.219\"\n\n[[package]]\nname = \"tokio\"\nversion = \"1.47.1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module app\n\nrequire github.com/gin-gonic/gin v1.9.1\n",
+        )
+        .unwrap();
+
+        let libraries = resolve_dependencies(
+            &CatalogBackend,
+            discover_dependencies(dir.path()),
+            Duration::from_secs(10),
+        )
+        .await;
+        let ids: Vec<Option<&str>> = libraries
+            .iter()
+            .map(|library| {
+                library
+                    .resolution
+                    .as_ref()
+                    .and_then(|resolution| resolution.as_ref().ok())
+                    .and_then(|id| id.as_deref())
+            })
+            .collect();
+
+        assert_eq!(
+            ids,
+            [
+                Some("/serde-rs/serde/1.0.219"),
+                Some("/tokio-rs/tokio"),
+                None,
+                None,
+                Some("/gin-gonic/gin/v1.9.1"),
+            ]
+        );
+        assert!(matches!(
+            libraries[3].resolution,
+            Some(Err(Context7Error::RateLimited { .. }))
+        ));
+    }
+
+    fn dependency(name: &str) -> ProjectDependency {
+        ProjectDependency {
+            name: name.to_string(),
+            ecosystem: Ecosystem::Rust,
+            requirement: None,
+            locked_version: None,
+            manifest: "Cargo.toml".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolution_is_capped() {
+        let dependencies = (0..MAX_RESOLVED_DEPENDENCIES + 10)
+            .map(|n| dependency(&format!("dep{n}")))
+            .collect();
+
+        let libraries =
+            resolve_dependencies(&CatalogBackend, dependencies, Duration::from_secs(10)).await;
+
+        assert_eq!(libraries.len(), MAX_RESOLVED_DEPENDENCIES + 10);
+        let looked_up = libraries
+            .iter()
+            .take_while(|library| library.resolution.is_some())
+            .count();
+        assert_eq!(looked_up, MAX_RESOLVED_DEPENDENCIES);
+        assert!(
+            libraries[looked_up..]
+                .iter()
+                .all(|l| l.resolution.is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolution_returns_partial_results_at_deadline() {
+        let dependencies = ["serde", "stalled", "tokio"].map(dependency).to_vec();
+
+        let libraries =
+            resolve_dependencies(&CatalogBackend, dependencies, Duration::from_millis(100)).await;
+
+        let names: Vec<&str> = libraries
+            .iter()
+            .map(|library| library.dependency.name.as_str())
+            .collect();
+        assert_eq!(names, ["serde", "stalled", "tokio"]);
+        assert!(matches!(
+            libraries[0].resolution,
+            Some(Ok(Some(ref id))) if id == "/serde-rs/serde"
+        ));
+        assert!(libraries[1].resolution.is_none());
+        assert!(libraries[2].resolution.is_none());
+    }
+
+    #[test]
+    fn test_path_from_file_uri() {
+        assert_eq!(
+            path_from_file_uri("file:///home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(
+            path_from_file_uri("file://localhost/srv/app"),
+            Some(PathBuf::from("/srv/app"))
+        );
+        assert_eq!(path_from_file_uri("https://example.com/app"), None);
+    }
+}
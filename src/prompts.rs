@@ -0,0 +1,191 @@
+use rmcp::model::{JsonObject, Prompt, PromptArgument};
+
+use crate::backend::DocsBackend;
+use crate::error::Context7Error;
+use crate::version::select_version;
+
+/// A documentation prompt with its arguments filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocsPrompt {
+    ExplainApi {
+        library: String,
+        topic: String,
+    },
+    MigrationGuide {
+        library: String,
+        from: String,
+        to: String,
+    },
+    CompareLibraries {
+        a: String,
+        b: String,
+    },
+}
+
+/// Documentation a prompt embeds, before the library is resolved to an ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptDocument {
+    /// A library name or Context7-compatible library ID, as the user gave it.
+    pub library: String,
+    pub version: Option<String>,
+    pub topic: Option<String>,
+}
+
+/// The prompts advertised by `prompts/list`.
+#[must_use]
+pub fn prompt_definitions() -> Vec<Prompt> {
+    let argument = |name: &str, description: &str| PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(true),
+    };
+    let library = argument(
+        "library",
+        "Library name (e.g., 'next.js') or Context7-compatible library ID (e.g., '/vercel/next.js')",
+    );
+    vec![
+        Prompt::new(
+            "explain-api",
+            Some("Explain part of a library's API using its current documentation"),
+            Some(vec![
+                library.clone(),
+                argument(
+                    "topic",
+                    "API or feature to explain (e.g., 'hooks', 'routing')",
+                ),
+            ]),
+        ),
+        Prompt::new(
+            "migration-guide",
+            Some("Write a guide for upgrading a library between two versions"),
+            Some(vec![
+                library,
+                argument("from", "Version currently in use (e.g., '13')"),
+                argument("to", "Version to upgrade to (e.g., '14.2')"),
+            ]),
+        ),
+        Prompt::new(
+            "compare-libraries",
+            Some("Compare two libraries side by side using their documentation"),
+            Some(vec![
+                argument("a", "First library name or Context7-compatible library ID"),
+                argument("b", "Second library name or Context7-compatible library ID"),
+            ]),
+        ),
+    ]
+}
+
+impl DocsPrompt {
+    /// Reads a `prompts/get` request, explaining what is missing from an incomplete one.
+    pub fn parse(name: &str, arguments: Option<&JsonObject>) -> Result<Self, String> {
+        let argument = |key: &str| {
+            arguments
+                .and_then(|args| args.get(key))
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string)
+                .ok_or_else(|| format!("Missing required argument '{key}' for prompt {name}"))
+        };
+        match name {
+            "explain-api" => Ok(Self::ExplainApi {
+                library: argument("library")?,
+                topic: argument("topic")?,
+            }),
+            "migration-guide" => Ok(Self::MigrationGuide {
+                library: argument("library")?,
+                from: argument("from")?,
+                to: argument("to")?,
+            }),
+            "compare-libraries" => Ok(Self::CompareLibraries {
+                a: argument("a")?,
+                b: argument("b")?,
+            }),
+            _ => Err(format!("Unknown prompt: {name}")),
+        }
+    }
+
+    /// The documentation to embed ahead of the instructions.
+    #[must_use]
+    pub fn documents(&self) -> Vec<PromptDocument> {
+        let document = |library: &str, version: Option<&str>, topic: Option<&str>| PromptDocument {
+            library: library.to_string(),
+            version: version.map(ToString::to_string),
+            topic: topic.map(ToString::to_string),
+        };
+        match self {
+            Self::ExplainApi { library, topic } => vec![document(library, None, Some(topic))],
+            Self::MigrationGuide { library, from, to } => vec![
+                document(library, Some(from), None),
+                document(library, Some(to), Some("migration")),
+            ],
+            Self::CompareLibraries { a, b } => {
+                vec![document(a, None, None), document(b, None, None)]
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn description(&self) -> String {
+        match self {
+            Self::ExplainApi { library, topic } => format!("Explain {topic} in {library}"),
+            Self::MigrationGuide { library, from, to } => {
+                format!("Migrate {library} from {from} to {to}")
+            }
+            Self::CompareLibraries { a, b } => format!("Compare {a} and {b}"),
+        }
+    }
+
+    /// The request that follows the embedded documentation.
+    #[must_use]
+    pub fn instructions(&self) -> String {
+        match self {
+            Self::ExplainApi { library, topic } => format!(
+                "Using the {library} documentation above, explain {topic}: what it is for, the main functions and types involved with their parameters, and common pitfalls. Finish with a short, idiomatic code example. Rely only on the documentation provided and say so when something is not covered by it."
+            ),
+            Self::MigrationGuide { library, from, to } => format!(
+                "Using the {library} documentation above, write a step-by-step guide for upgrading from version {from} to version {to}. List breaking changes, removed or renamed APIs and newly recommended patterns, each with a before/after code example. Rely only on the documentation provided and point out anything it does not cover."
+            ),
+            Self::CompareLibraries { a, b } => format!(
+                "Using the documentation above, compare {a} and {b}: their purpose, API style, main features and limitations, and when to choose each. Show the same small task implemented with both. Rely only on the documentation provided."
+            ),
+        }
+    }
+}
+
+/// Resolves a library name or ID to the Context7-compatible ID to fetch.
+///
+/// With a `version`, the best matching `/org/project/version` ID is chosen
+/// from the versions the library publishes, falling back to the unversioned ID.
+pub async fn resolve_library(
+    backend: &dyn DocsBackend,
+    library: &str,
+    version: Option<&str>,
+) -> Result<String, Context7Error> {
+    let library = library.trim();
+    let id = library.starts_with('/').then_some(library);
+    if let Some(id) = id
+        && (version.is_none() || id.trim_matches('/').split('/').count() > 2)
+    {
+        return Ok(id.to_string());
+    }
+
+    let query = id.and_then(|id| id.rsplit('/').next()).unwrap_or(library);
+    let response = backend.search_libraries(query).await?;
+    let best = match id {
+        Some(id) => response.results.iter().find(|result| result.id == id),
+        None => response.results.first(),
+    };
+    let Some(best) = best else {
+        return id.map(ToString::to_string).ok_or(Context7Error::NotFound);
+    };
+
+    let selection = version
+        .zip(best.versions.as_deref())
+        .and_then(|(version, versions)| select_version(versions, version));
+    Ok(selection.map_or_else(
+        || best.id.clone(),
+        |selection| format!("{}/{}", best.id, selection.version()),
+    ))
+}
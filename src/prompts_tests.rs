@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Context7Error, DocsBackend, DocsPrompt, SearchResponse, SearchResult, prompt_definitions,
+        resolve_library,
+    };
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    #[test]
+    fn test_prompt_definitions_match_parsed_prompts() {
+        for prompt in prompt_definitions() {
+            let arguments = prompt
+                .arguments
+                .iter()
+                .flatten()
+                .map(|argument| (argument.name.clone(), json!("x")))
+                .collect();
+            assert!(DocsPrompt::parse(&prompt.name, Some(&arguments)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_parse_requires_every_argument() {
+        let arguments = json!({ "library": "next.js", "from": "13", "to": " " });
+        let error = DocsPrompt::parse("migration-guide", arguments.as_object()).unwrap_err();
+        assert_eq!(
+            error,
+            "Missing required argument 'to' for prompt migration-guide"
+        );
+
+        assert!(DocsPrompt::parse("summarize", None).is_err());
+
+        let prompt = DocsPrompt::parse(
+            "migration-guide",
+            json!({ "library": "next.js", "from": "13", "to": "14" }).as_object(),
+        )
+        .unwrap();
+        let documents = prompt.documents();
+        assert_eq!(documents[0].version.as_deref(), Some("13"));
+        assert_eq!(documents[1].version.as_deref(), Some("14"));
+        assert_eq!(documents[1].topic.as_deref(), Some("migration"));
+    }
+
+    struct VersionedBackend;
+
+    #[async_trait]
+    impl DocsBackend for VersionedBackend {
+        async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+            let results = if query == "next.js" {
+                vec![SearchResult {
+                    id: "/vercel/next.js".to_string(),
+                    title: "Next.js".to_string(),
+                    description: String::new(),
+                    total_snippets: None,
+                    trust_score: None,
+                    versions: Some(vec!["v13.5.6".to_string(), "v14.2.0".to_string()]),
+                    origin: None,
+                }]
+            } else {
+                Vec::new()
+            };
+            Ok(SearchResponse {
+                results,
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            _library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_library_picks_versioned_ids() {
+        let backend = VersionedBackend;
+        assert_eq!(
+            resolve_library(&backend, "next.js", None).await.unwrap(),
+            "/vercel/next.js"
+        );
+        assert_eq!(
+            resolve_library(&backend, "next.js", Some("13"))
+                .await
+                .unwrap(),
+            "/vercel/next.js/v13.5.6"
+        );
+        assert_eq!(
+            resolve_library(&backend, "/vercel/next.js", Some("^14"))
+                .await
+                .unwrap(),
+            "/vercel/next.js/v14.2.0"
+        );
+        assert_eq!(
+            resolve_library(&backend, "/someone/else", Some("2"))
+                .await
+                .unwrap(),
+            "/someone/else"
+        );
+        assert!(matches!(
+            resolve_library(&backend, "unknown", None).await,
+            Err(Context7Error::NotFound)
+        ));
+    }
+}
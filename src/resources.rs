@@ -0,0 +1,239 @@
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+/// URI scheme under which library documentation is exposed as MCP resources.
+pub const RESOURCE_SCHEME: &str = "context7";
+
+/// RFC 6570 template advertised for documentation resources.
+pub const LIBRARY_URI_TEMPLATE: &str = "context7://{org}/{project}{/version}{?topic,tokens}";
+
+/// How many recently read libraries `resources/list` remembers.
+const RECENT_CAPACITY: usize = 50;
+
+/// A `context7://org/project[/version]?topic=..&tokens=..` resource URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryUri {
+    pub library_id: String,
+    pub topic: Option<String>,
+    pub tokens: Option<u32>,
+}
+
+impl LibraryUri {
+    #[must_use]
+    pub fn new(library_id: &str, topic: Option<&str>, tokens: Option<u32>) -> Self {
+        Self {
+            library_id: format!("/{}", library_id.trim_matches('/')),
+            topic: topic.filter(|t| !t.is_empty()).map(ToString::to_string),
+            tokens,
+        }
+    }
+
+    /// Parses a resource URI, explaining what is wrong with a malformed one.
+    pub fn parse(uri: &str) -> Result<Self, String> {
+        let rest = uri
+            .strip_prefix(RESOURCE_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(|| format!("expected a {RESOURCE_SCHEME}:// URI, got '{uri}'"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let segments = path
+            .trim_end_matches('/')
+            .split('/')
+            .map(|segment| percent_decode(segment).ok_or_else(|| format!("invalid URI '{uri}'")))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !(2..=3).contains(&segments.len()) || segments.iter().any(String::is_empty) {
+            return Err(format!(
+                "expected {RESOURCE_SCHEME}://org/project or {RESOURCE_SCHEME}://org/project/version, got '{uri}'"
+            ));
+        }
+
+        let mut topic = None;
+        let mut tokens = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(&value.replace('+', " "))
+                .ok_or_else(|| format!("invalid query in '{uri}'"))?;
+            match key {
+                "topic" => topic = Some(value).filter(|t| !t.is_empty()),
+                "tokens" if !value.is_empty() => {
+                    tokens = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("tokens must be a number, got '{value}'"))?,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            library_id: format!("/{}", segments.join("/")),
+            topic,
+            tokens,
+        })
+    }
+
+    /// A short human-readable label, e.g. `/vercel/next.js (routing)`.
+    #[must_use]
+    pub fn name(&self) -> String {
+        match &self.topic {
+            Some(topic) => format!("{} ({topic})", self.library_id),
+            None => self.library_id.clone(),
+        }
+    }
+}
+
+impl fmt::Display for LibraryUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{RESOURCE_SCHEME}:/{}", self.library_id)?;
+        let mut separator = '?';
+        if let Some(topic) = &self.topic {
+            write!(f, "{separator}topic={}", percent_encode(topic))?;
+            separator = '&';
+        }
+        if let Some(tokens) = self.tokens {
+            write!(f, "{separator}tokens={tokens}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Most-recently-read documentation resources, newest first.
+#[derive(Debug, Default)]
+pub struct RecentLibraries {
+    entries: Mutex<VecDeque<LibraryUri>>,
+}
+
+impl RecentLibraries {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, uri: LibraryUri) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        entries.retain(|entry| *entry != uri);
+        entries.push_front(uri);
+        entries.truncate(RECENT_CAPACITY);
+    }
+
+    #[must_use]
+    pub fn list(&self) -> Vec<LibraryUri> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Resources one session subscribed to, with a digest of the content it last saw.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    state: Mutex<SubscriptionState>,
+}
+
+#[derive(Debug, Default)]
+struct SubscriptionState {
+    digests: HashMap<String, Option<[u8; 32]>>,
+    refreshing: bool,
+}
+
+impl Subscriptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes to `uri`, remembering `content` as already seen.
+    ///
+    /// Returns `true` when no refresh task is running yet and the caller should start one.
+    pub fn subscribe(&self, uri: &str, content: Option<&str>) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        state.digests.insert(uri.to_string(), content.map(digest));
+        !std::mem::replace(&mut state.refreshing, true)
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.digests.remove(uri);
+        }
+    }
+
+    #[must_use]
+    pub fn uris(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|state| state.digests.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Records `content` for a subscribed `uri`; `true` if it changed since last seen.
+    pub fn observe(&self, uri: &str, content: &str) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let Some(seen) = state.digests.get_mut(uri) else {
+            return false;
+        };
+        let current = digest(content);
+        let changed = seen.is_some_and(|previous| previous != current);
+        *seen = Some(current);
+        changed
+    }
+
+    /// Stops the refresh task once nothing is subscribed; returns whether it should exit.
+    pub fn stop_if_idle(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        if state.digests.is_empty() {
+            state.refreshing = false;
+        }
+        !state.refreshing
+    }
+
+    /// Drops every subscription, e.g. when the session has gone away.
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.digests.clear();
+            state.refreshing = false;
+        }
+    }
+}
+
+fn digest(content: &str) -> [u8; 32] {
+    Sha256::digest(content.as_bytes()).into()
+}
+
+/// Decodes `%XX` escapes, returning `None` for malformed ones or invalid UTF-8.
+pub(crate) fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut input = text.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let hex = [input.next()?, input.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
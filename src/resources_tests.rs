@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use crate::{LibraryUri, RecentLibraries, Subscriptions};
+
+    #[test]
+    fn test_library_uri_parse() {
+        let uri =
+            LibraryUri::parse("context7://vercel/next.js/v14.2.0?topic=app%20router&tokens=3000")
+                .unwrap();
+        assert_eq!(uri.library_id, "/vercel/next.js/v14.2.0");
+        assert_eq!(uri.topic.as_deref(), Some("app router"));
+        assert_eq!(uri.tokens, Some(3000));
+
+        let uri = LibraryUri::parse("context7://nixos/nix").unwrap();
+        assert_eq!(uri, LibraryUri::new("/nixos/nix", None, None));
+
+        assert!(LibraryUri::parse("https://nixos/nix").is_err());
+        assert!(LibraryUri::parse("context7://nix").is_err());
+        assert!(LibraryUri::parse("context7://a/b/c/d").is_err());
+        assert!(LibraryUri::parse("context7://nixos/nix?tokens=lots").is_err());
+    }
+
+    #[test]
+    fn test_library_uri_roundtrip() {
+        let uri = LibraryUri::new(
+            "/vercel/next.js",
+            Some("data fetching & caching"),
+            Some(2000),
+        );
+        let text = uri.to_string();
+        assert_eq!(
+            text,
+            "context7://vercel/next.js?topic=data%20fetching%20%26%20caching&tokens=2000"
+        );
+        assert_eq!(LibraryUri::parse(&text).unwrap(), uri);
+    }
+
+    #[test]
+    fn test_recent_libraries_keep_newest_first_without_duplicates() {
+        let recent = RecentLibraries::new();
+        recent.record(LibraryUri::new("/a/one", None, None));
+        recent.record(LibraryUri::new("/b/two", Some("setup"), None));
+        recent.record(LibraryUri::new("/a/one", None, None));
+
+        let ids: Vec<String> = recent.list().into_iter().map(|uri| uri.name()).collect();
+        assert_eq!(ids, ["/a/one", "/b/two (setup)"]);
+    }
+
+    #[test]
+    fn test_subscriptions_report_changed_content() {
+        let subscriptions = Subscriptions::new();
+        assert!(subscriptions.subscribe("context7://a/one", Some("v1")));
+        assert!(!subscriptions.subscribe("context7://b/two", None));
+
+        assert!(!subscriptions.observe("context7://a/one", "v1"));
+        assert!(subscriptions.observe("context7://a/one", "v2"));
+        assert!(!subscriptions.observe("context7://a/one", "v2"));
+        // Without a baseline the first content seen is not a change.
+        assert!(!subscriptions.observe("context7://b/two", "v1"));
+        assert!(!subscriptions.observe("context7://c/unsubscribed", "v1"));
+
+        assert!(!subscriptions.stop_if_idle());
+        subscriptions.unsubscribe("context7://a/one");
+        subscriptions.unsubscribe("context7://b/two");
+        assert!(subscriptions.stop_if_idle());
+        assert!(subscriptions.subscribe("context7://a/one", None));
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::ErrorKind;
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+const DEFAULT_DEADLINE: Duration = Duration::from_secs(30);
+
+/// How failed Context7 requests are retried.
+///
+/// Every call, retries included, finishes within `deadline`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            deadline: DEFAULT_DEADLINE,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that gives up after the first failure.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `attempt + 1`, or `None` once retries are exhausted.
+    ///
+    /// A server-provided `Retry-After` wins; otherwise the delay doubles per attempt,
+    /// capped at `max_delay`, with the upper half randomised to spread out clients.
+    #[must_use]
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        if retry_after.is_some() {
+            return retry_after;
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_delay);
+        let half = backoff / 2;
+        Some(half + half.mul_f64(fastrand::f64()))
+    }
+}
+
+/// Statuses worth retrying: rate limiting and server-side failures.
+#[must_use]
+pub const fn is_retryable_status(status: u16) -> bool {
+    status == 429 || matches!(status, 500..=599)
+}
+
+/// Transport errors worth retrying: refused, reset or dropped connections.
+#[must_use]
+pub fn is_retryable_error(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::ConnectionFailed => true,
+        ureq::Error::Io(e) => matches!(
+            e.kind(),
+            ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof
+        ),
+        _ => false,
+    }
+}
+
+/// Parses a `Retry-After` header given either as delay-seconds or an HTTP date.
+#[must_use]
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+}
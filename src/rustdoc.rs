@@ -0,0 +1,795 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::backend::DocsBackend;
+use crate::client::{DEFAULT_TOKENS, MINIMUM_TOKENS, SearchResponse, SearchResult};
+use crate::error::Context7Error;
+use crate::local::SECTION_SEPARATOR;
+use crate::tokens::estimate_tokens;
+
+/// Organisation segment of the library IDs this backend serves.
+const CRATES_ORG: &str = "crates";
+
+/// A crate loaded from one rustdoc JSON file.
+struct Crate {
+    name: String,
+    version: Option<String>,
+    items: Vec<DocItem>,
+}
+
+/// What a search reports about a crate, kept so unchanged files are not re-parsed.
+#[derive(Debug, Clone)]
+struct CrateSummary {
+    name: String,
+    version: Option<String>,
+    description: String,
+    snippets: usize,
+}
+
+impl From<Crate> for CrateSummary {
+    fn from(krate: Crate) -> Self {
+        let snippets = krate
+            .items
+            .iter()
+            .map(|item| item.docs.matches("```").count() / 2)
+            .sum();
+        let description = krate
+            .items
+            .first()
+            .and_then(|root| first_paragraph(&root.docs))
+            .unwrap_or_else(|| format!("Rust API documentation for {}", krate.name));
+        Self {
+            name: krate.name,
+            version: krate.version,
+            description,
+            snippets,
+        }
+    }
+}
+
+/// Summaries by file, with the modification time they were read at; `None` for
+/// files that are not rustdoc JSON.
+type SummaryCache = HashMap<PathBuf, (SystemTime, Option<CrateSummary>)>;
+
+/// A documented public item, flattened out of the module tree.
+#[derive(Debug)]
+struct DocItem {
+    path: String,
+    signature: Option<String>,
+    docs: String,
+}
+
+/// Serves crates documented with `rustdoc --output-format json` as `/crates/<name>`.
+///
+/// Each `*.json` file in the configured directories (usually `target/doc`) is one
+/// crate. Files are re-read whenever their modification time changes so a fresh
+/// `cargo doc` shows up without a restart.
+#[derive(Clone)]
+pub struct RustdocBackend {
+    dirs: Vec<PathBuf>,
+    summaries: Arc<Mutex<SummaryCache>>,
+}
+
+impl RustdocBackend {
+    #[must_use]
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        Self {
+            dirs,
+            summaries: Arc::default(),
+        }
+    }
+
+    fn json_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn load_crate(&self, name: &str) -> Option<Crate> {
+        let name = normalize_crate_name(name);
+        let files = self.json_files();
+        // rustdoc names the file after the crate, so try that before parsing the rest.
+        let (likely, others): (Vec<_>, Vec<_>) = files.into_iter().partition(|path| {
+            path.file_stem()
+                .is_some_and(|stem| normalize_crate_name(&stem.to_string_lossy()) == name)
+        });
+        likely
+            .iter()
+            .chain(&others)
+            .filter_map(|path| read_crate(path))
+            .find(|krate| normalize_crate_name(&krate.name) == name)
+    }
+
+    /// Summarises every crate, parsing only files that changed since the last call.
+    fn crate_summaries(&self) -> Vec<CrateSummary> {
+        let files = self.json_files();
+        let Ok(mut cache) = self.summaries.lock() else {
+            return Vec::new();
+        };
+        cache.retain(|path, _| files.contains(path));
+
+        files
+            .into_iter()
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                if let Some((seen, summary)) = cache.get(&path)
+                    && *seen == modified
+                {
+                    return summary.clone();
+                }
+                let summary = read_crate(&path).map(CrateSummary::from);
+                cache.insert(path, (modified, summary.clone()));
+                summary
+            })
+            .collect()
+    }
+
+    fn search_crates(&self, query: &str) -> Vec<SearchResult> {
+        let query = normalize_crate_name(query.trim());
+        self.crate_summaries()
+            .into_iter()
+            .filter(|krate| {
+                let name = normalize_crate_name(&krate.name);
+                name.contains(&query) || query.contains(&name)
+            })
+            .map(|krate| SearchResult {
+                id: format!("/{CRATES_ORG}/{}", krate.name),
+                title: krate.name,
+                description: krate.description,
+                total_snippets: i32::try_from(krate.snippets).ok(),
+                trust_score: None,
+                versions: krate.version.map(|version| vec![version]),
+                origin: None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DocsBackend for RustdocBackend {
+    async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+        let backend = self.clone();
+        let query = query.to_string();
+        let results = tokio::task::spawn_blocking(move || backend.search_crates(&query))
+            .await
+            .map_err(|e| Context7Error::Internal(e.to_string()))?;
+        Ok(SearchResponse {
+            results,
+            error: None,
+        })
+    }
+
+    async fn fetch_library_documentation(
+        &self,
+        library_id: &str,
+        tokens: Option<u32>,
+        topic: Option<&str>,
+    ) -> Result<Option<String>, Context7Error> {
+        let mut segments = library_id.trim_matches('/').split('/');
+        let (Some(CRATES_ORG), Some(name), None) =
+            (segments.next(), segments.next(), segments.next())
+        else {
+            return Err(Context7Error::NotFound);
+        };
+
+        let backend = self.clone();
+        let name = name.to_string();
+        let tokens = tokens.unwrap_or(DEFAULT_TOKENS).max(MINIMUM_TOKENS);
+        let topic = topic.map(str::to_lowercase);
+
+        tokio::task::spawn_blocking(move || {
+            let krate = backend.load_crate(&name).ok_or(Context7Error::NotFound)?;
+            Ok(select_items(&krate, topic.as_deref(), tokens))
+        })
+        .await
+        .map_err(|e| Context7Error::Internal(e.to_string()))?
+    }
+}
+
+/// Crate names are interchangeable with `-` and `_` and case-insensitive.
+fn normalize_crate_name(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+fn first_paragraph(docs: &str) -> Option<String> {
+    docs.split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with('#') && !p.starts_with("```"))
+        .map(|p| p.replace('\n', " "))
+}
+
+/// Picks the items whose paths match `topic`, best first, within the token budget.
+fn select_items(krate: &Crate, topic: Option<&str>, tokens: u32) -> Option<String> {
+    let ranked: Vec<&DocItem> = match topic {
+        Some(topic) => {
+            let prefix = format!("{}::", normalize_crate_name(&krate.name));
+            let topic = topic
+                .trim()
+                .trim_start_matches("crate::")
+                .trim_start_matches(&prefix);
+            let mut ranked: Vec<(usize, &DocItem)> = krate
+                .items
+                .iter()
+                .map(|item| (topic_score(item, &prefix, topic), item))
+                .filter(|(score, _)| *score > 0)
+                .collect();
+            ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            ranked.into_iter().map(|(_, item)| item).collect()
+        }
+        None => krate.items.iter().collect(),
+    };
+
+    let budget = tokens as usize;
+    let mut used = 0;
+    let mut parts = Vec::new();
+    for item in ranked {
+        let text = match &item.signature {
+            Some(signature) => format!(
+                "### {}\n\n```rust\n{signature}\n```\n\n{}",
+                item.path, item.docs
+            ),
+            None => format!("### {}\n\n{}", item.path, item.docs),
+        };
+        let text = text.trim_end().to_string();
+        let cost = estimate_tokens(&text);
+        if !parts.is_empty() && used + cost > budget {
+            break;
+        }
+        used += cost;
+        parts.push(text);
+    }
+
+    (!parts.is_empty()).then(|| parts.join(SECTION_SEPARATOR))
+}
+
+/// Ranks exact paths above path suffixes, module prefixes and plain mentions.
+fn topic_score(item: &DocItem, crate_prefix: &str, topic: &str) -> usize {
+    let path = item.path.to_lowercase();
+    let path = path.strip_prefix(crate_prefix).unwrap_or(&path);
+    if topic.is_empty() {
+        return 0;
+    }
+    if path == topic {
+        return 100;
+    }
+    if path.ends_with(&format!("::{topic}")) {
+        return 50;
+    }
+    if path.starts_with(&format!("{topic}::")) {
+        return 30;
+    }
+    if path.contains(topic) {
+        return 20;
+    }
+
+    let docs = item.docs.to_lowercase();
+    topic
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .filter(|word| !word.is_empty())
+        .map(|word| 3 * usize::from(path.contains(word)) + usize::from(docs.contains(word)))
+        .sum()
+}
+
+fn read_crate(path: &Path) -> Option<Crate> {
+    let text = fs::read_to_string(path).ok()?;
+    let json: Value = match serde_json::from_str(&text) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::debug!("Skipping {}: {e}", path.display());
+            return None;
+        }
+    };
+    let index = json.get("index")?;
+    let root = index.get(id_key(json.get("root")?)?)?;
+    let name = root.get("name")?.as_str()?.to_string();
+
+    let mut walker = Walker {
+        index,
+        items: Vec::new(),
+        visited: HashSet::new(),
+    };
+    walker.visit(root, &name);
+
+    Some(Crate {
+        name,
+        version: json
+            .get("crate_version")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        items: walker.items,
+    })
+}
+
+/// Item IDs are numbers in current rustdoc JSON and strings in older formats.
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// Returns the kind and payload of an item's `inner`, e.g. `("function", {...})`.
+fn item_inner(item: &Value) -> Option<(&str, &Value)> {
+    match item.get("inner")? {
+        Value::Object(map) => map
+            .iter()
+            .next()
+            .map(|(kind, value)| (kind.as_str(), value)),
+        Value::String(kind) => Some((kind.as_str(), &Value::Null)),
+        _ => None,
+    }
+}
+
+fn docs(item: &Value) -> String {
+    item.get("docs")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Walks the module tree from the crate root, collecting public items by path.
+struct Walker<'a> {
+    index: &'a Value,
+    items: Vec<DocItem>,
+    visited: HashSet<String>,
+}
+
+impl<'a> Walker<'a> {
+    fn lookup(&self, id: &Value) -> Option<&'a Value> {
+        let key = id_key(id)?;
+        self.index.get(key)
+    }
+
+    fn ids(value: &Value, field: &str) -> Vec<Value> {
+        value
+            .get(field)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn visit(&mut self, item: &'a Value, path: &str) {
+        if let Some(id) = item.get("id").and_then(id_key)
+            && !self.visited.insert(id)
+        {
+            return;
+        }
+        let Some((kind, inner)) = item_inner(item) else {
+            return;
+        };
+        let name = path.rsplit("::").next().unwrap_or(path);
+
+        match kind {
+            "module" => {
+                self.push(path, None, item);
+                for child in Self::ids(inner, "items") {
+                    let Some(child) = self.lookup(&child) else {
+                        continue;
+                    };
+                    if let Some(("use", import)) = item_inner(child) {
+                        self.visit_import(import, path);
+                    } else if let Some(child_name) = child.get("name").and_then(Value::as_str) {
+                        self.visit(child, &format!("{path}::{child_name}"));
+                    }
+                }
+            }
+            "struct" | "enum" | "union" => {
+                let signature = match kind {
+                    "struct" => struct_signature(self, name, inner),
+                    "enum" => enum_signature(self, name, inner),
+                    _ => format!("union {name}{}", generics(inner.get("generics"))),
+                };
+                self.push(path, Some(signature), item);
+                for impl_id in Self::ids(inner, "impls") {
+                    let Some(("impl", imp)) = self.lookup(&impl_id).and_then(item_inner) else {
+                        continue;
+                    };
+                    // Trait impls are documented on the trait; keep inherent methods.
+                    if !imp.get("trait").is_none_or(Value::is_null) {
+                        continue;
+                    }
+                    self.visit_members(imp, path);
+                }
+            }
+            "trait" => {
+                let signature = format!("trait {name}{}", generics(inner.get("generics")));
+                self.push(path, Some(signature), item);
+                self.visit_members(inner, path);
+            }
+            _ => {
+                let signature = item_signature(name, kind, inner);
+                self.push(path, signature, item);
+            }
+        }
+    }
+
+    /// Follows a non-glob `pub use` to a local item, documenting it under the new path.
+    fn visit_import(&mut self, import: &'a Value, module_path: &str) {
+        if import.get("is_glob").and_then(Value::as_bool) == Some(true) {
+            return;
+        }
+        let (Some(target), Some(name)) = (
+            import.get("id").and_then(|id| self.lookup(id)),
+            import.get("name").and_then(Value::as_str),
+        ) else {
+            return;
+        };
+        self.visit(target, &format!("{module_path}::{name}"));
+    }
+
+    fn visit_members(&mut self, container: &'a Value, path: &str) {
+        for member in Self::ids(container, "items") {
+            let Some(member) = self.lookup(&member) else {
+                continue;
+            };
+            if let Some(member_name) = member.get("name").and_then(Value::as_str) {
+                self.visit(member, &format!("{path}::{member_name}"));
+            }
+        }
+    }
+
+    fn push(&mut self, path: &str, signature: Option<String>, item: &Value) {
+        self.items.push(DocItem {
+            path: path.to_string(),
+            signature,
+            docs: docs(item),
+        });
+    }
+}
+
+fn item_signature(name: &str, kind: &str, inner: &Value) -> Option<String> {
+    Some(match kind {
+        "function" => function_signature(name, inner),
+        "constant" => {
+            let value = inner
+                .pointer("/const/expr")
+                .and_then(Value::as_str)
+                .map(|expr| format!(" = {expr}"))
+                .unwrap_or_default();
+            format!("const {name}: {}{value}", render_type(&inner["type"]))
+        }
+        "static" => format!("static {name}: {}", render_type(&inner["type"])),
+        "type_alias" => format!(
+            "type {name}{} = {}",
+            generics(inner.get("generics")),
+            render_type(&inner["type"])
+        ),
+        "assoc_type" => format!("type {name}{}", bounds(inner.get("bounds"))),
+        "assoc_const" => format!("const {name}: {}", render_type(&inner["type"])),
+        "macro" => inner.as_str()?.to_string(),
+        _ => return None,
+    })
+}
+
+fn function_signature(name: &str, function: &Value) -> String {
+    let header = &function["header"];
+    let mut signature = String::new();
+    for (flag, keyword) in [
+        ("is_const", "const "),
+        ("is_async", "async "),
+        ("is_unsafe", "unsafe "),
+    ] {
+        if header.get(flag).and_then(Value::as_bool) == Some(true) {
+            signature.push_str(keyword);
+        }
+    }
+
+    // Older formats call the signature `decl`.
+    let sig = function.get("sig").unwrap_or(&function["decl"]);
+    let inputs: Vec<String> = sig
+        .get("inputs")
+        .and_then(Value::as_array)
+        .map(|inputs| {
+            inputs
+                .iter()
+                .map(|input| {
+                    let arg = input[0].as_str().unwrap_or("_");
+                    let ty = render_type(&input[1]);
+                    match (arg, ty.as_str()) {
+                        ("self", "Self") => "self".to_string(),
+                        ("self", "&Self") => "&self".to_string(),
+                        ("self", "&mut Self") => "&mut self".to_string(),
+                        _ => format!("{arg}: {ty}"),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    signature.push_str(&format!(
+        "fn {name}{}({})",
+        generics(function.get("generics")),
+        inputs.join(", ")
+    ));
+    if let Some(output) = sig.get("output").filter(|o| !o.is_null()) {
+        signature.push_str(" -> ");
+        signature.push_str(&render_type(output));
+    }
+    signature
+}
+
+fn struct_signature(walker: &Walker<'_>, name: &str, inner: &Value) -> String {
+    let generics = generics(inner.get("generics"));
+    let field = |id: &Value| {
+        walker.lookup(id).and_then(|field| {
+            let (_, ty) = item_inner(field)?;
+            Some((field.get("name")?.as_str()?.to_string(), render_type(ty)))
+        })
+    };
+
+    match inner.get("kind") {
+        Some(Value::Object(kind)) if kind.contains_key("plain") => {
+            let fields: Vec<String> = Walker::ids(&kind["plain"], "fields")
+                .iter()
+                .filter_map(field)
+                .map(|(name, ty)| format!("    pub {name}: {ty},\n"))
+                .collect();
+            if fields.is_empty() {
+                format!("struct {name}{generics} {{ /* private fields */ }}")
+            } else {
+                format!("struct {name}{generics} {{\n{}}}", fields.concat())
+            }
+        }
+        Some(Value::Object(kind)) if kind.contains_key("tuple") => {
+            let fields: Vec<String> = kind["tuple"]
+                .as_array()
+                .map(|ids| {
+                    ids.iter()
+                        .map(|id| field(id).map_or_else(|| "_".to_string(), |(_, ty)| ty))
+                        .collect()
+                })
+                .unwrap_or_default();
+            format!("struct {name}{generics}({});", fields.join(", "))
+        }
+        _ => format!("struct {name}{generics};"),
+    }
+}
+
+fn enum_signature(walker: &Walker<'_>, name: &str, inner: &Value) -> String {
+    let variants: Vec<String> = Walker::ids(inner, "variants")
+        .iter()
+        .filter_map(|id| walker.lookup(id)?.get("name")?.as_str())
+        .map(|variant| format!("    {variant},\n"))
+        .collect();
+    format!(
+        "enum {name}{} {{\n{}}}",
+        generics(inner.get("generics")),
+        variants.concat()
+    )
+}
+
+fn generics(generics: Option<&Value>) -> String {
+    let params: Vec<String> = generics
+        .and_then(|g| g.get("params"))
+        .and_then(Value::as_array)
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(|param| {
+                    let name = param.get("name")?.as_str()?;
+                    let kind = param.get("kind")?;
+                    if let Some(ty) = kind.get("type") {
+                        // `impl Trait` arguments show up as synthetic parameters.
+                        if ty.get("is_synthetic").and_then(Value::as_bool) == Some(true) {
+                            return None;
+                        }
+                        Some(format!("{name}{}", bounds(ty.get("bounds"))))
+                    } else if let Some(constant) = kind.get("const") {
+                        Some(format!("const {name}: {}", render_type(&constant["type"])))
+                    } else {
+                        Some(name.to_string())
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn bounds(bounds: Option<&Value>) -> String {
+    let rendered = render_bounds(bounds);
+    if rendered.is_empty() {
+        rendered
+    } else {
+        format!(": {rendered}")
+    }
+}
+
+fn render_bounds(bounds: Option<&Value>) -> String {
+    bounds
+        .and_then(Value::as_array)
+        .map(|bounds| {
+            bounds
+                .iter()
+                .filter_map(|bound| {
+                    if let Some(trait_bound) = bound.get("trait_bound") {
+                        let maybe =
+                            trait_bound.get("modifier").and_then(Value::as_str) == Some("maybe");
+                        let path = render_path(&trait_bound["trait"]);
+                        Some(if maybe { format!("?{path}") } else { path })
+                    } else {
+                        bound.get("outlives")?.as_str().map(ToString::to_string)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" + ")
+        })
+        .unwrap_or_default()
+}
+
+fn render_path(path: &Value) -> String {
+    // Older formats call the path `name`.
+    let name = path
+        .get("path")
+        .or_else(|| path.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or("_");
+    format!("{name}{}", render_generic_args(path.get("args")))
+}
+
+fn render_generic_args(args: Option<&Value>) -> String {
+    let Some(args) = args.filter(|a| !a.is_null()) else {
+        return String::new();
+    };
+    if let Some(angle) = args.get("angle_bracketed") {
+        let mut rendered: Vec<String> = angle
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| {
+                args.iter()
+                    .filter_map(|arg| {
+                        if let Some(ty) = arg.get("type") {
+                            Some(render_type(ty))
+                        } else if let Some(lifetime) = arg.get("lifetime") {
+                            lifetime.as_str().map(ToString::to_string)
+                        } else if let Some(constant) = arg.get("const") {
+                            constant
+                                .get("expr")
+                                .and_then(Value::as_str)
+                                .map(ToString::to_string)
+                        } else {
+                            arg.as_str().map(ToString::to_string)
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(constraints) = angle.get("constraints").and_then(Value::as_array) {
+            rendered.extend(constraints.iter().filter_map(|constraint| {
+                let name = constraint.get("name")?.as_str()?;
+                let ty = constraint.pointer("/binding/equality/type")?;
+                Some(format!("{name} = {}", render_type(ty)))
+            }));
+        }
+        if rendered.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", rendered.join(", "))
+        }
+    } else if let Some(parenthesized) = args.get("parenthesized") {
+        let inputs: Vec<String> = parenthesized
+            .get("inputs")
+            .and_then(Value::as_array)
+            .map(|inputs| inputs.iter().map(render_type).collect())
+            .unwrap_or_default();
+        let output = parenthesized
+            .get("output")
+            .filter(|o| !o.is_null())
+            .map(|o| format!(" -> {}", render_type(o)))
+            .unwrap_or_default();
+        format!("({}){output}", inputs.join(", "))
+    } else {
+        String::new()
+    }
+}
+
+/// Renders a rustdoc JSON type back into Rust syntax.
+fn render_type(ty: &Value) -> String {
+    let Value::Object(map) = ty else {
+        return "_".to_string();
+    };
+    let Some((kind, value)) = map.iter().next() else {
+        return "_".to_string();
+    };
+
+    match kind.as_str() {
+        "resolved_path" => render_path(value),
+        "generic" | "primitive" => value.as_str().unwrap_or("_").to_string(),
+        "tuple" => {
+            let types: Vec<String> = value
+                .as_array()
+                .map(|types| types.iter().map(render_type).collect())
+                .unwrap_or_default();
+            if types.len() == 1 {
+                format!("({},)", types[0])
+            } else {
+                format!("({})", types.join(", "))
+            }
+        }
+        "slice" => format!("[{}]", render_type(value)),
+        "array" => format!(
+            "[{}; {}]",
+            render_type(&value["type"]),
+            value.get("len").and_then(Value::as_str).unwrap_or("_")
+        ),
+        "borrowed_ref" => {
+            let lifetime = value
+                .get("lifetime")
+                .and_then(Value::as_str)
+                .map(|l| format!("{l} "))
+                .unwrap_or_default();
+            let mutable = is_mutable(value);
+            format!(
+                "&{lifetime}{}{}",
+                if mutable { "mut " } else { "" },
+                render_type(&value["type"])
+            )
+        }
+        "raw_pointer" => format!(
+            "*{} {}",
+            if is_mutable(value) { "mut" } else { "const" },
+            render_type(&value["type"])
+        ),
+        "impl_trait" => format!("impl {}", render_bounds(Some(value))),
+        "dyn_trait" => {
+            let mut traits: Vec<String> = value
+                .get("traits")
+                .and_then(Value::as_array)
+                .map(|traits| traits.iter().map(|t| render_path(&t["trait"])).collect())
+                .unwrap_or_default();
+            if let Some(lifetime) = value.get("lifetime").and_then(Value::as_str) {
+                traits.push(lifetime.to_string());
+            }
+            format!("dyn {}", traits.join(" + "))
+        }
+        "qualified_path" => {
+            let name = value.get("name").and_then(Value::as_str).unwrap_or("_");
+            let self_type = render_type(&value["self_type"]);
+            match value.get("trait").filter(|t| !t.is_null()) {
+                Some(tr) => format!("<{self_type} as {}>::{name}", render_path(tr)),
+                None => format!("{self_type}::{name}"),
+            }
+        }
+        "function_pointer" => {
+            let sig = value.get("sig").unwrap_or(&value["decl"]);
+            let inputs: Vec<String> = sig
+                .get("inputs")
+                .and_then(Value::as_array)
+                .map(|inputs| inputs.iter().map(|input| render_type(&input[1])).collect())
+                .unwrap_or_default();
+            let output = sig
+                .get("output")
+                .filter(|o| !o.is_null())
+                .map(|o| format!(" -> {}", render_type(o)))
+                .unwrap_or_default();
+            format!("fn({}){output}", inputs.join(", "))
+        }
+        _ => "_".to_string(),
+    }
+}
+
+/// Older formats call the flag `mutable`.
+fn is_mutable(value: &Value) -> bool {
+    value
+        .get("is_mutable")
+        .or_else(|| value.get("mutable"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
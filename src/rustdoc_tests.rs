@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod tests {
+    use crate::{Context7Error, DocsBackend, RustdocBackend};
+    use serde_json::json;
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// A trimmed-down `rustdoc --output-format json` file for crate `my_crate`.
+    fn write_rustdoc(dir: &Path) {
+        let doc = json!({
+            "root": 0,
+            "crate_version": "0.3.1",
+            "format_version": 57,
+            "index": {
+                "0": {
+                    "id": 0, "name": "my_crate",
+                    "docs": "Helpers for talking to the billing service.\n\n```\nmy_crate::net::connect(\"billing\");\n```",
+                    "inner": {"module": {"is_crate": true, "items": [1, 2]}}
+                },
+                "1": {
+                    "id": 1, "name": "net",
+                    "docs": "Networking.",
+                    "inner": {"module": {"is_crate": false, "items": [3, 4]}}
+                },
+                "2": {
+                    "id": 2, "name": "VERSION", "docs": null,
+                    "inner": {"constant": {"type": {"primitive": "u32"}, "const": {"expr": "3"}}}
+                },
+                "3": {
+                    "id": 3, "name": "connect",
+                    "docs": "Opens a connection to `host`.",
+                    "inner": {"function": {
+                        "sig": {
+                            "inputs": [["host", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"primitive": "str"}}}]],
+                            "output": {"resolved_path": {"path": "Result", "id": 9, "args": {"angle_bracketed": {"args": [
+                                {"type": {"resolved_path": {"path": "Client", "id": 4, "args": null}}},
+                                {"type": {"resolved_path": {"path": "String", "id": 10, "args": null}}}
+                            ], "constraints": []}}}}
+                        },
+                        "generics": {"params": [], "where_predicates": []},
+                        "header": {"is_const": false, "is_unsafe": false, "is_async": true}
+                    }}
+                },
+                "4": {
+                    "id": 4, "name": "Client",
+                    "docs": "A connected client.",
+                    "inner": {"struct": {
+                        "kind": {"plain": {"fields": [5], "has_stripped_fields": true}},
+                        "generics": {"params": [], "where_predicates": []},
+                        "impls": [6, 8]
+                    }}
+                },
+                "5": {
+                    "id": 5, "name": "timeout", "docs": null,
+                    "inner": {"struct_field": {"primitive": "u64"}}
+                },
+                "6": {
+                    "id": 6, "name": null, "docs": null,
+                    "inner": {"impl": {"trait": null, "items": [7]}}
+                },
+                "7": {
+                    "id": 7, "name": "send",
+                    "docs": "Sends a request.",
+                    "inner": {"function": {
+                        "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "is_mutable": true, "type": {"generic": "Self"}}}]], "output": null},
+                        "generics": {"params": [], "where_predicates": []},
+                        "header": {"is_const": false, "is_unsafe": false, "is_async": false}
+                    }}
+                },
+                "8": {
+                    "id": 8, "name": null, "docs": null,
+                    "inner": {"impl": {"trait": {"path": "Send", "id": 11, "args": null}, "items": []}}
+                }
+            },
+            "paths": {}
+        });
+        fs::write(dir.join("my_crate.json"), doc.to_string()).unwrap();
+        fs::write(dir.join("broken.json"), "{ not rustdoc").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rustdoc_search_lists_crates() {
+        let dir = tempfile::tempdir().unwrap();
+        write_rustdoc(dir.path());
+        let backend = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+
+        let response = backend.search_libraries("my-crate").await.unwrap();
+
+        assert_eq!(response.results.len(), 1);
+        let result = &response.results[0];
+        assert_eq!(result.id, "/crates/my_crate");
+        assert_eq!(
+            result.description,
+            "Helpers for talking to the billing service."
+        );
+        assert_eq!(result.versions, Some(vec!["0.3.1".to_string()]));
+        assert_eq!(result.total_snippets, Some(1));
+
+        let none = backend.search_libraries("serde").await.unwrap();
+        assert!(none.results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rustdoc_search_reparses_only_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_rustdoc(dir.path());
+        let backend = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+        let versions = || async {
+            backend
+                .search_libraries("my_crate")
+                .await
+                .unwrap()
+                .results
+                .into_iter()
+                .flat_map(|result| result.versions.unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(versions().await, ["0.3.1"]);
+
+        let path = dir.path().join("my_crate.json");
+        let doc = fs::read_to_string(&path).unwrap();
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        let set_modified = |time| {
+            fs::File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+
+        // Same modification time: the earlier parse is reused.
+        fs::write(&path, doc.replace("0.3.1", "0.4.0")).unwrap();
+        set_modified(modified);
+        assert_eq!(versions().await, ["0.3.1"]);
+
+        set_modified(modified + Duration::from_secs(1));
+        assert_eq!(versions().await, ["0.4.0"]);
+
+        fs::remove_file(&path).unwrap();
+        assert!(versions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rustdoc_fetch_renders_signatures() {
+        let dir = tempfile::tempdir().unwrap();
+        write_rustdoc(dir.path());
+        let backend = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+
+        let docs = backend
+            .fetch_library_documentation("/crates/my_crate", None, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(docs.starts_with("### my_crate\n\nHelpers for talking"));
+        assert!(docs.contains(
+            "### my_crate::net::connect\n\n```rust\nasync fn connect(host: &str) -> Result<Client, String>\n```\n\nOpens a connection to `host`."
+        ));
+        assert!(docs.contains("struct Client {\n    pub timeout: u64,\n}"));
+        assert!(
+            docs.contains("### my_crate::net::Client::send\n\n```rust\nfn send(&mut self)\n```")
+        );
+        assert!(docs.contains("const VERSION: u32 = 3"));
+    }
+
+    #[tokio::test]
+    async fn test_rustdoc_fetch_matches_topic_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        write_rustdoc(dir.path());
+        let backend = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+
+        let docs = backend
+            .fetch_library_documentation("/crates/my_crate", None, Some("net::Client"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(docs.starts_with("### my_crate::net::Client\n"));
+        assert!(docs.contains("my_crate::net::Client::send"));
+        assert!(!docs.contains("VERSION"));
+
+        let docs = backend
+            .fetch_library_documentation("/crates/my_crate", None, Some("send"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(docs.starts_with("### my_crate::net::Client::send\n"));
+    }
+
+    #[tokio::test]
+    async fn test_rustdoc_fetch_unknown_crate_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        write_rustdoc(dir.path());
+        let backend = RustdocBackend::new(vec![dir.path().to_path_buf()]);
+
+        let error = backend
+            .fetch_library_documentation("/crates/serde", None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Context7Error::NotFound));
+
+        let error = backend
+            .fetch_library_documentation("/org/my_crate", None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Context7Error::NotFound));
+    }
+}
@@ -1,34 +1,594 @@
 use anyhow::Result;
+use futures::StreamExt;
 use rmcp::{
     RoleServer, ServiceExt,
-    handler::server::ServerHandler,
+    handler::server::{ServerHandler, tool::cached_schema_for_type},
     model::{
-        CallToolRequestParam, CallToolResult, Content, ErrorData, Implementation, InitializeResult,
-        ListToolsResult, PaginatedRequestParam, ProtocolVersion, ServerCapabilities, Tool,
-        ToolsCapability,
+        AnnotateAble, CallToolRequestParam, CallToolResult, CompleteRequestParam, CompleteResult,
+        Content, ErrorData, GetPromptRequestParam, GetPromptResult, Implementation,
+        InitializeResult, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, PaginatedRequestParam, ProgressNotificationParam, PromptMessage,
+        PromptMessageRole, PromptsCapability, ProtocolVersion, RawResource, RawResourceTemplate,
+        ReadResourceRequestParam, ReadResourceResult, ResourceContents,
+        ResourceUpdatedNotificationParam, ResourcesCapability, ServerCapabilities,
+        SetLevelRequestParam, SubscribeRequestParam, Tool, ToolsCapability,
+        UnsubscribeRequestParam,
+    },
+    serde_json::Map,
+    service::{Peer, RequestContext},
+    transport::{
+        self, SseServer, StreamableHttpServerConfig, StreamableHttpService,
+        sse_server::SseServerConfig, streamable_http_server::session::local::LocalSessionManager,
     },
-    serde_json::{Map, Value},
-    service::RequestContext,
-    transport,
 };
 use std::env;
+use std::net::SocketAddr;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
+use crate::backend::DocsBackend;
 use crate::client::Context7Client;
-use crate::formatting::format_search_results;
+use crate::completion::{TopicIndex, completion_info, matching_values};
+use crate::error::Context7Error;
+use crate::formatting::{
+    DOCS_NOT_FOUND, format_library_docs_entry, format_project_libraries, format_search_results,
+    format_version_selection,
+};
+use crate::logging::{McpLogger, SessionLogger};
+use crate::origin::AllowedOrigins;
+use crate::project::{
+    RESOLVE_DEADLINE, discover_dependencies, path_from_file_uri, resolve_dependencies,
+};
+use crate::prompts::{DocsPrompt, PromptDocument, prompt_definitions, resolve_library};
+use crate::resources::{LIBRARY_URI_TEMPLATE, LibraryUri, RecentLibraries, Subscriptions};
+use crate::tokens::ResponseLimit;
+use crate::tools::{
+    GetLibraryDocsArgs, GetMultipleLibraryDocsArgs, ListProjectLibrariesArgs, ResolveLibraryArgs,
+    ResolveLibraryOutput, parse_arguments,
+};
+
+/// How often subscribed resources are re-fetched by default.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
 
 #[derive(Clone)]
 pub struct Context7Tool {
-    client: Arc<Context7Client>,
+    backend: Arc<dyn DocsBackend>,
+    recent: Arc<RecentLibraries>,
+    topics: Arc<TopicIndex>,
+    subscriptions: Arc<Subscriptions>,
+    refresh_interval: Duration,
+    logger: McpLogger,
+    session_logger: Arc<SessionLogger>,
+    response_limit: Option<ResponseLimit>,
+    allowed_origins: Arc<AllowedOrigins>,
+    working_dir_roots: bool,
 }
 
 impl Context7Tool {
     #[must_use]
     pub fn new(api_key: Option<String>, insecure: bool) -> Self {
+        Self::with_backend(Arc::new(Context7Client::new(api_key, insecure)))
+    }
+
+    /// Creates a tool around an existing client so several sessions can share it.
+    #[must_use]
+    pub fn with_client(client: Arc<Context7Client>) -> Self {
+        Self::with_backend(client)
+    }
+
+    /// Creates a tool serving documentation from any backend.
+    #[must_use]
+    pub fn with_backend(backend: Arc<dyn DocsBackend>) -> Self {
+        let logger = McpLogger::new();
         Self {
-            client: Arc::new(Context7Client::new(api_key, insecure)),
+            backend,
+            recent: Arc::new(RecentLibraries::new()),
+            topics: Arc::new(TopicIndex::new()),
+            subscriptions: Arc::new(Subscriptions::new()),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            session_logger: Arc::new(logger.session()),
+            logger,
+            response_limit: None,
+            allowed_origins: Arc::new(AllowedOrigins::default()),
+            working_dir_roots: false,
         }
     }
+
+    /// Sets how often subscribed resources are checked for changes.
+    #[must_use]
+    pub const fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Truncates documentation longer than `limit` before it is returned.
+    #[must_use]
+    pub const fn with_response_limit(mut self, limit: ResponseLimit) -> Self {
+        self.response_limit = Some(limit);
+        self
+    }
+
+    /// Falls back to the server's working directory for clients without
+    /// workspace roots. Only sensible when the client started the server itself,
+    /// as over stdio; a shared server would expose its own project to everyone.
+    #[must_use]
+    pub const fn with_working_dir_roots(mut self, enabled: bool) -> Self {
+        self.working_dir_roots = enabled;
+        self
+    }
+
+    /// Lets the HTTP transports serve `origins` besides loopback ones.
+    #[must_use]
+    pub fn with_allowed_origins(mut self, origins: AllowedOrigins) -> Self {
+        self.allowed_origins = Arc::new(origins);
+        self
+    }
+
+    /// Forwards log events that reach `logger`'s tracing layer to clients that
+    /// enable logging with `logging/setLevel`.
+    #[must_use]
+    pub fn with_logger(mut self, logger: McpLogger) -> Self {
+        self.session_logger = Arc::new(logger.session());
+        self.logger = logger;
+        self
+    }
+
+    /// A copy for a new MCP session: shares the backend, but not subscriptions
+    /// or the log level.
+    #[must_use]
+    pub fn for_session(&self) -> Self {
+        Self {
+            subscriptions: Arc::new(Subscriptions::new()),
+            session_logger: Arc::new(self.logger.session()),
+            ..self.clone()
+        }
+    }
+
+    /// Fetches the documentation behind a resource URI and remembers it for
+    /// `resources/list` and topic completion.
+    ///
+    /// Subscriptions see the full content, as refreshes do; only what is returned
+    /// is cut down to the response limit.
+    async fn read_library(&self, uri: &LibraryUri) -> Result<Option<String>, Context7Error> {
+        let documentation = self
+            .backend
+            .fetch_library_documentation(&uri.library_id, uri.tokens, uri.topic.as_deref())
+            .await?;
+        if let Some(documentation) = &documentation {
+            self.recent.record(uri.clone());
+            self.topics.record(&uri.library_id, documentation);
+            self.subscriptions.observe(&uri.to_string(), documentation);
+        }
+        Ok(documentation.map(|documentation| self.limit_response(uri, documentation)))
+    }
+
+    /// Truncates `documentation` to the response limit, if one is set.
+    fn limit_response(&self, uri: &LibraryUri, documentation: String) -> String {
+        let Some(limit) = &self.response_limit else {
+            return documentation;
+        };
+        match limit.truncate(&documentation) {
+            Some(truncated) => {
+                tracing::info!(
+                    "Truncated documentation for {uri} to {} tokens",
+                    limit.max_tokens
+                );
+                truncated
+            }
+            None => documentation,
+        }
+    }
+
+    /// Reads several libraries, a few at a time, keeping their order.
+    async fn read_libraries(
+        &self,
+        uris: Vec<LibraryUri>,
+    ) -> Vec<(LibraryUri, Result<Option<String>, Context7Error>)> {
+        futures::stream::iter(uris)
+            .map(|uri| async move {
+                let result = self.read_library(&uri).await;
+                (uri, result)
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Library IDs read in this server or present in the documentation cache.
+    async fn known_library_ids(&self) -> Vec<String> {
+        let backend = Arc::clone(&self.backend);
+        let cached = tokio::task::spawn_blocking(move || backend.cached_libraries())
+            .await
+            .unwrap_or_default();
+        let mut ids: Vec<String> = self
+            .recent
+            .list()
+            .into_iter()
+            .map(|uri| uri.library_id)
+            .collect();
+        ids.extend(cached);
+        ids
+    }
+
+    /// Known libraries matching `value`, followed by what a search for it finds.
+    async fn complete_library(&self, value: &str, as_id: bool) -> Vec<String> {
+        let known = self.known_library_ids().await.into_iter().filter_map(|id| {
+            if as_id {
+                Some(id)
+            } else {
+                id.trim_matches('/')
+                    .split('/')
+                    .nth(1)
+                    .map(ToString::to_string)
+            }
+        });
+        let mut values = matching_values(known, value);
+
+        let query = value.trim().trim_matches('/').replace('/', " ");
+        if query.chars().count() >= 2 {
+            match self.backend.suggest_libraries(&query).await {
+                Ok(response) => {
+                    for result in response.results {
+                        let suggestion = if as_id { result.id } else { result.title };
+                        if !values.contains(&suggestion) {
+                            values.push(suggestion);
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("Completion search for '{query}' failed: {e}"),
+            }
+        }
+        values
+    }
+
+    /// Runs the tool a `tools/call` request names.
+    async fn dispatch_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match request.name.as_ref() {
+            "resolve-library-id" => {
+                let args: ResolveLibraryArgs = parse_arguments(&request.name, request.arguments)
+                    .map_err(|message| ErrorData::invalid_params(message, None))?;
+                let version = args.version();
+
+                match self.backend.search_libraries(&args.library_name).await {
+                    Ok(response) => {
+                        response.error.as_ref().map_or_else(|| {
+                            let output = ResolveLibraryOutput::new(&response, version);
+                            let results_text = format_search_results(&response);
+                            let selection_text = version
+                                .zip(response.results.first().zip(output.best_match.as_ref()))
+                                .map(|(version, (best, selected))| {
+                                    format!("{}\n\n", format_version_selection(best, version, selected.version.as_ref()))
+                                })
+                                .unwrap_or_default();
+                            let text = format!(
+                                "{selection_text}Available Libraries (top matches):\n\nEach result includes:\n- Library ID: Context7-compatible identifier (format: /org/project)\n- Name: Library or package name\n- Description: Short summary\n- Code Snippets: Number of available code examples\n- Trust Score: Authority indicator\n- Versions: List of versions if available. Use one of those versions if the user provides a version in their query. The format of the version is /org/project/version.\n\nFor best results, select libraries based on name match, trust score, snippet coverage, and relevance to your use case.\n\n----------\n\n{results_text}"
+                            );
+                            let structured = serde_json::to_value(&output)
+                                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+                            Ok(CallToolResult {
+                                content: vec![Content::text(text)],
+                                structured_content: Some(structured),
+                                is_error: Some(false),
+                                meta: None,
+                            })
+                        }, |error| Ok(CallToolResult::error(vec![Content::text(format!("Error [search_failed]: {error}"))])))
+                    }
+                    Err(e) => Ok(error_result(&e)),
+                }
+            }
+            "get-library-docs" => {
+                let args: GetLibraryDocsArgs = parse_arguments(&request.name, request.arguments)
+                    .map_err(|message| ErrorData::invalid_params(message, None))?;
+                let uri = LibraryUri::new(
+                    &args.library_id,
+                    args.topic.as_deref(),
+                    args.tokens.map(NonZeroU32::get),
+                );
+
+                match self.read_library(&uri).await {
+                    Ok(Some(documentation)) => {
+                        Ok(CallToolResult::success(vec![Content::text(documentation)]))
+                    }
+                    Ok(None) => Ok(CallToolResult::success(vec![Content::text(DOCS_NOT_FOUND)])),
+                    Err(e) => Ok(error_result(&e)),
+                }
+            }
+            "get-multiple-library-docs" => {
+                let args: GetMultipleLibraryDocsArgs =
+                    parse_arguments(&request.name, request.arguments)
+                        .map_err(|message| ErrorData::invalid_params(message, None))?;
+                let tokens = args
+                    .validate()
+                    .and_then(|()| args.library_tokens())
+                    .map_err(|message| ErrorData::invalid_params(message, None))?;
+                let uris = args
+                    .libraries
+                    .iter()
+                    .zip(tokens)
+                    .map(|(library, tokens)| {
+                        LibraryUri::new(&library.library_id, library.topic.as_deref(), tokens)
+                    })
+                    .collect();
+
+                let results = self.read_libraries(uris).await;
+                let all_failed = results.iter().all(|(_, result)| result.is_err());
+                let content = results
+                    .iter()
+                    .map(|(uri, result)| {
+                        Content::text(format_library_docs_entry(
+                            &uri.library_id,
+                            uri.topic.as_deref(),
+                            result,
+                        ))
+                    })
+                    .collect();
+                Ok(if all_failed {
+                    CallToolResult::error(content)
+                } else {
+                    CallToolResult::success(content)
+                })
+            }
+            "list-project-libraries" => {
+                let _: ListProjectLibrariesArgs = parse_arguments(&request.name, request.arguments)
+                    .map_err(|message| ErrorData::invalid_params(message, None))?;
+                let roots = workspace_roots(context, self.working_dir_roots).await;
+                if roots.is_empty() {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "Error [no_roots]: the client shared no workspace roots to read dependencies from",
+                    )]));
+                }
+                // One deadline for the whole call, however many roots there are.
+                let deadline = tokio::time::Instant::now() + RESOLVE_DEADLINE;
+                let mut sections = Vec::new();
+                for root in roots {
+                    let dependencies = tokio::task::spawn_blocking({
+                        let root = root.clone();
+                        move || discover_dependencies(&root)
+                    })
+                    .await
+                    .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+                    let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                    let libraries =
+                        resolve_dependencies(self.backend.as_ref(), dependencies, remaining).await;
+                    sections.push(format_project_libraries(
+                        &root.display().to_string(),
+                        &libraries,
+                    ));
+                }
+                Ok(CallToolResult::success(vec![Content::text(
+                    sections.join("\n\n----------\n\n"),
+                )]))
+            }
+            _ => Err(ErrorData::invalid_request(
+                format!("Unknown tool: {}", request.name),
+                None,
+            )),
+        }
+    }
+
+    /// Resolves and fetches one document a prompt embeds.
+    async fn embed_document(&self, document: &PromptDocument) -> Result<PromptMessage, ErrorData> {
+        let library_id = resolve_library(
+            self.backend.as_ref(),
+            &document.library,
+            document.version.as_deref(),
+        )
+        .await
+        .map_err(|e| match e {
+            Context7Error::NotFound => ErrorData::invalid_params(
+                format!("No library found matching '{}'", document.library),
+                None,
+            ),
+            e => ErrorData::internal_error(format!("Error [{}]: {e}", e.code()), None),
+        })?;
+
+        let uri = LibraryUri::new(&library_id, document.topic.as_deref(), None);
+        match self.read_library(&uri).await {
+            Ok(Some(documentation)) => Ok(PromptMessage::new_resource(
+                PromptMessageRole::User,
+                uri.to_string(),
+                Some(DOCS_MIME_TYPE.to_string()),
+                Some(documentation),
+                None,
+                None,
+                None,
+            )),
+            Ok(None) | Err(Context7Error::NotFound) => Ok(PromptMessage::new_text(
+                PromptMessageRole::User,
+                format!("No documentation was found for {}.", uri.name()),
+            )),
+            Err(e) => Err(ErrorData::internal_error(
+                format!("Error [{}]: {e}", e.code()),
+                None,
+            )),
+        }
+    }
+
+    /// Periodically re-fetches subscribed resources, notifying `peer` of changes.
+    ///
+    /// Exits once every resource is unsubscribed or the session closes.
+    fn spawn_refresh(&self, peer: Peer<RoleServer>) {
+        let tool = self.clone();
+        let span = self.session_logger.span();
+        tokio::spawn(
+            async move {
+                let mut interval = tokio::time::interval(tool.refresh_interval);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if peer.is_transport_closed() {
+                        tool.subscriptions.clear();
+                        return;
+                    }
+                    if tool.subscriptions.stop_if_idle() {
+                        return;
+                    }
+                    for uri in tool.subscriptions.uris() {
+                        let Ok(library) = LibraryUri::parse(&uri) else {
+                            continue;
+                        };
+                        let refreshed = tool
+                            .backend
+                            .refresh_library_documentation(
+                                &library.library_id,
+                                library.tokens,
+                                library.topic.as_deref(),
+                            )
+                            .await;
+                        match refreshed {
+                            Ok(Some(documentation)) => {
+                                if !tool.subscriptions.observe(&uri, &documentation) {
+                                    continue;
+                                }
+                                tracing::debug!("Documentation for {uri} changed");
+                                let notified = peer
+                                    .notify_resource_updated(ResourceUpdatedNotificationParam {
+                                        uri: uri.clone(),
+                                    })
+                                    .await;
+                                if let Err(e) = notified {
+                                    tracing::debug!("Stopping refresh, session gone: {e}");
+                                    tool.subscriptions.clear();
+                                    return;
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!("Refreshing {uri} failed: {e}"),
+                        }
+                    }
+                }
+            }
+            .instrument(span),
+        );
+    }
+}
+
+/// How many libraries of a batched fetch are requested at once.
+const BATCH_CONCURRENCY: usize = 4;
+
+/// How often a running tool call reports progress to clients that asked for it.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a cancelled tool call checks whether its session has ended.
+const CANCELLED_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// MIME type of documentation served as resources.
+const DOCS_MIME_TYPE: &str = "text/markdown";
+
+/// Reports a backend failure as an MCP tool error carrying its stable code.
+fn error_result(error: &Context7Error) -> CallToolResult {
+    tracing::debug!("Tool call failed: {error:?}");
+    CallToolResult::error(vec![Content::text(format!(
+        "Error [{}]: {error}",
+        error.code()
+    ))])
+}
+
+/// Runs a tool call, reporting progress while it lasts and giving up on it as
+/// soon as the client cancels the request.
+///
+/// Dropping `call` on cancellation also aborts any HTTP request it has in flight.
+/// A cancelled request gets no response, as MCP requires: rmcp sends whatever
+/// the handler returns, so the handler only returns once the session is gone.
+async fn track_tool_call(
+    context: &RequestContext<RoleServer>,
+    tool: &str,
+    call: impl Future<Output = Result<CallToolResult, ErrorData>>,
+) -> Result<CallToolResult, ErrorData> {
+    let progress_token = context.meta.get_progress_token();
+    let started = tokio::time::Instant::now();
+    let mut ticks = tokio::time::interval_at(started + PROGRESS_INTERVAL, PROGRESS_INTERVAL);
+    let mut progress = 0.0;
+    let mut call = Box::pin(call);
+
+    loop {
+        tokio::select! {
+            result = &mut call => return result,
+            () = context.ct.cancelled() => {
+                tracing::debug!("Tool call {tool} cancelled by the client");
+                break;
+            }
+            _ = ticks.tick(), if progress_token.is_some() => {
+                progress += 1.0;
+                let Some(progress_token) = progress_token.clone() else {
+                    continue;
+                };
+                let notification = ProgressNotificationParam {
+                    progress_token,
+                    progress,
+                    total: None,
+                    message: Some(format!(
+                        "{tool} running for {}s",
+                        started.elapsed().as_secs()
+                    )),
+                };
+                if let Err(e) = context.peer.notify_progress(notification).await {
+                    tracing::debug!("Failed to send progress for {tool}: {e}");
+                }
+            }
+        }
+    }
+
+    drop(call);
+    while !context.peer.is_transport_closed() {
+        tokio::time::sleep(CANCELLED_POLL_INTERVAL).await;
+    }
+    Err(ErrorData::internal_error(
+        format!("Tool call {tool} was cancelled"),
+        None,
+    ))
+}
+
+/// The client's workspace roots, or, if `working_dir` allows, the working
+/// directory when it has none.
+async fn workspace_roots(context: &RequestContext<RoleServer>, working_dir: bool) -> Vec<PathBuf> {
+    let supports_roots = context
+        .peer
+        .peer_info()
+        .is_some_and(|info| info.capabilities.roots.is_some());
+    if supports_roots {
+        match context.peer.list_roots().await {
+            Ok(result) => {
+                let roots: Vec<PathBuf> = result
+                    .roots
+                    .iter()
+                    .filter_map(|root| path_from_file_uri(&root.uri))
+                    .collect();
+                if !roots.is_empty() {
+                    return roots;
+                }
+            }
+            Err(e) => tracing::warn!("Could not list workspace roots: {e}"),
+        }
+    }
+    if !working_dir {
+        return Vec::new();
+    }
+    env::current_dir().into_iter().collect()
+}
+
+/// How the MCP server is exposed to clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// A single session over stdin/stdout.
+    Stdio,
+    /// MCP Streamable HTTP on `/mcp`, one session per `Mcp-Session-Id`.
+    StreamableHttp(SocketAddr),
+    /// Legacy HTTP+SSE: `GET /sse` opens the stream, `POST /message` sends requests.
+    Sse(SocketAddr),
+    /// Newline-delimited JSON-RPC on a Unix socket, one session per connection.
+    #[cfg(unix)]
+    Unix(PathBuf),
 }
 
 impl ServerHandler for Context7Tool {
@@ -37,6 +597,13 @@ impl ServerHandler for Context7Tool {
             protocol_version: ProtocolVersion::LATEST,
             capabilities: ServerCapabilities {
                 tools: Some(ToolsCapability::default()),
+                prompts: Some(PromptsCapability::default()),
+                completions: Some(Map::new()),
+                logging: Some(Map::new()),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: None,
+                }),
                 ..Default::default()
             },
             server_info: Implementation {
@@ -55,184 +622,506 @@ impl ServerHandler for Context7Tool {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, ErrorData> {
-        let mut tools = Vec::new();
-
-        let mut resolve_schema = Map::new();
-        resolve_schema.insert("type".to_string(), Value::String("object".to_string()));
-        let mut resolve_props = Map::new();
-        let mut library_name_prop = Map::new();
-        library_name_prop.insert("type".to_string(), Value::String("string".to_string()));
-        library_name_prop.insert(
-            "description".to_string(),
-            Value::String(
-                "Library name to search for and retrieve a Context7-compatible library ID."
-                    .to_string(),
-            ),
-        );
-        resolve_props.insert("libraryName".to_string(), Value::Object(library_name_prop));
-        resolve_schema.insert("properties".to_string(), Value::Object(resolve_props));
-        resolve_schema.insert(
-            "required".to_string(),
-            Value::Array(vec![Value::String("libraryName".to_string())]),
-        );
+        let tools = vec![
+            Tool {
+                name: "resolve-library-id".into(),
+                title: None,
+                description: Some("Resolves a package/product name to a Context7-compatible library ID and returns a list of matching libraries.\n\nYou MUST call this function before 'get-library-docs' to obtain a valid Context7-compatible library ID UNLESS the user explicitly provides a library ID in the format '/org/project' or '/org/project/version' in their query.\n\nSelection Process:\n1. Analyze the query to understand what library/package the user is looking for\n2. Return the most relevant match based on:\n- Name similarity to the query (exact matches prioritized)\n- Description relevance to the query's intent\n- Documentation coverage (prioritize libraries with higher Code Snippet counts)\n- Trust score (consider libraries with scores of 7-10 more authoritative)\n\nResponse Format:\n- Return the selected library ID in a clearly marked section\n- Provide a brief explanation for why this library was chosen\n- If multiple good matches exist, acknowledge this but proceed with the most relevant one\n- If no good matches exist, clearly state this and suggest query refinements\n\nFor ambiguous queries, request clarification before proceeding with a best-guess match.".into()),
+                input_schema: cached_schema_for_type::<ResolveLibraryArgs>(),
+                output_schema: Some(cached_schema_for_type::<ResolveLibraryOutput>()),
+                annotations: None,
+                icons: None,
+            },
+            Tool {
+                name: "get-library-docs".into(),
+                title: None,
+                description: Some("Fetches up-to-date documentation for a library. You must call 'resolve-library-id' first to obtain the exact Context7-compatible library ID required to use this tool, UNLESS the user explicitly provides a library ID in the format '/org/project' or '/org/project/version' in their query.".into()),
+                input_schema: cached_schema_for_type::<GetLibraryDocsArgs>(),
+                output_schema: None,
+                annotations: None,
+                icons: None,
+            },
+            Tool {
+                name: "get-multiple-library-docs".into(),
+                title: None,
+                description: Some("Fetches up-to-date documentation for several libraries at once (up to 10), e.g. a framework, its ORM and its test library. Each entry takes a Context7-compatible library ID obtained from 'resolve-library-id' (or given by the user in the format '/org/project' or '/org/project/version') with an optional topic and token limit.\n\nPass 'totalTokens' to share one token budget across the libraries, optionally favouring some with a higher 'weight'.\n\nThe libraries are fetched concurrently and returned as one content block per entry, in request order; an entry that fails carries its own error without affecting the others.".into()),
+                input_schema: cached_schema_for_type::<GetMultipleLibraryDocsArgs>(),
+                output_schema: None,
+                annotations: None,
+                icons: None,
+            },
+            Tool {
+                name: "list-project-libraries".into(),
+                title: None,
+                description: Some("Lists the libraries the current project depends on, read from the manifests and lockfiles in the workspace roots (Cargo.toml/Cargo.lock, package.json with npm/yarn/pnpm lockfiles, pyproject.toml/requirements.txt, go.mod), with each library's Context7-compatible library ID and exact locked version.\n\nUse this instead of 'resolve-library-id' for libraries the project already uses: the returned IDs can be passed straight to 'get-library-docs' and are pinned to the locked version when Context7 has it.".into()),
+                input_schema: cached_schema_for_type::<ListProjectLibrariesArgs>(),
+                output_schema: None,
+                annotations: None,
+                icons: None,
+            },
+        ];
 
-        tools.push(Tool {
-            name: "resolve-library-id".into(),
-            title: None,
-            description: Some("Resolves a package/product name to a Context7-compatible library ID and returns a list of matching libraries.\n\nYou MUST call this function before 'get-library-docs' to obtain a valid Context7-compatible library ID UNLESS the user explicitly provides a library ID in the format '/org/project' or '/org/project/version' in their query.\n\nSelection Process:\n1. Analyze the query to understand what library/package the user is looking for\n2. Return the most relevant match based on:\n- Name similarity to the query (exact matches prioritized)\n- Description relevance to the query's intent\n- Documentation coverage (prioritize libraries with higher Code Snippet counts)\n- Trust score (consider libraries with scores of 7-10 more authoritative)\n\nResponse Format:\n- Return the selected library ID in a clearly marked section\n- Provide a brief explanation for why this library was chosen\n- If multiple good matches exist, acknowledge this but proceed with the most relevant one\n- If no good matches exist, clearly state this and suggest query refinements\n\nFor ambiguous queries, request clarification before proceeding with a best-guess match.".into()),
-            input_schema: Arc::new(resolve_schema),
-            output_schema: None,
-            annotations: None,
-            icons: None,
-        });
+        Ok(ListToolsResult {
+            tools,
+            next_cursor: None,
+        })
+    }
 
-        let mut docs_schema = Map::new();
-        docs_schema.insert("type".to_string(), Value::String("object".to_string()));
-        let mut docs_props = Map::new();
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: vec![
+                RawResourceTemplate {
+                    uri_template: LIBRARY_URI_TEMPLATE.to_string(),
+                    name: "library-docs".to_string(),
+                    title: Some("Library documentation".to_string()),
+                    description: Some("Up-to-date documentation for a Context7-compatible library ID, optionally focused on a topic and limited to a number of tokens.".to_string()),
+                    mime_type: Some(DOCS_MIME_TYPE.to_string()),
+                }
+                .no_annotation(),
+            ],
+            next_cursor: None,
+        })
+    }
 
-        let mut library_id_prop = Map::new();
-        library_id_prop.insert("type".to_string(), Value::String("string".to_string()));
-        library_id_prop.insert("description".to_string(), Value::String("Exact Context7-compatible library ID (e.g., '/mongodb/docs', '/vercel/next.js', '/supabase/supabase', '/vercel/next.js/v14.3.0-canary.87') retrieved from 'resolve-library-id' or directly from user query in the format '/org/project' or '/org/project/version'.".to_string()));
-        docs_props.insert(
-            "context7CompatibleLibraryID".to_string(),
-            Value::Object(library_id_prop),
-        );
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let recent = self.recent.list();
+        let backend = Arc::clone(&self.backend);
+        let cached = tokio::task::spawn_blocking(move || backend.cached_libraries())
+            .await
+            .unwrap_or_default();
 
-        let mut tokens_prop = Map::new();
-        tokens_prop.insert("type".to_string(), Value::String("number".to_string()));
-        tokens_prop.insert("description".to_string(), Value::String("Maximum number of tokens of documentation to retrieve (default: 5000). Higher values provide more context but consume more tokens.".to_string()));
-        docs_props.insert("tokens".to_string(), Value::Object(tokens_prop));
-
-        let mut topic_prop = Map::new();
-        topic_prop.insert("type".to_string(), Value::String("string".to_string()));
-        topic_prop.insert(
-            "description".to_string(),
-            Value::String(
-                "Topic to focus documentation on (e.g., 'hooks', 'routing').".to_string(),
-            ),
-        );
-        docs_props.insert("topic".to_string(), Value::Object(topic_prop));
-
-        docs_schema.insert("properties".to_string(), Value::Object(docs_props));
-        docs_schema.insert(
-            "required".to_string(),
-            Value::Array(vec![Value::String(
-                "context7CompatibleLibraryID".to_string(),
-            )]),
+        let resource = |uri: &LibraryUri, description: &str| {
+            RawResource {
+                description: Some(description.to_string()),
+                mime_type: Some(DOCS_MIME_TYPE.to_string()),
+                ..RawResource::new(uri.to_string(), uri.name())
+            }
+            .no_annotation()
+        };
+        let mut resources: Vec<_> = recent
+            .iter()
+            .map(|uri| resource(uri, "Recently fetched documentation"))
+            .collect();
+        resources.extend(
+            cached
+                .iter()
+                .filter(|library_id| !recent.iter().any(|uri| &uri.library_id == *library_id))
+                .map(|library_id| {
+                    resource(
+                        &LibraryUri::new(library_id, None, None),
+                        "Cached documentation",
+                    )
+                }),
         );
 
-        tools.push(Tool {
-            name: "get-library-docs".into(),
-            title: None,
-            description: Some("Fetches up-to-date documentation for a library. You must call 'resolve-library-id' first to obtain the exact Context7-compatible library ID required to use this tool, UNLESS the user explicitly provides a library ID in the format '/org/project' or '/org/project/version' in their query.".into()),
-            input_schema: Arc::new(docs_schema),
-            output_schema: None,
-            annotations: None,
-            icons: None,
-        });
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
 
-        Ok(ListToolsResult {
-            tools,
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        async move {
+            let uri = LibraryUri::parse(&request.uri)
+                .map_err(|message| ErrorData::invalid_params(message, None))?;
+
+            match self.read_library(&uri).await {
+                Ok(Some(documentation)) => Ok(ReadResourceResult {
+                    contents: vec![ResourceContents::TextResourceContents {
+                        uri: request.uri,
+                        mime_type: Some(DOCS_MIME_TYPE.to_string()),
+                        text: documentation,
+                        meta: None,
+                    }],
+                }),
+                Ok(None) | Err(Context7Error::NotFound) => Err(ErrorData::resource_not_found(
+                    format!("No documentation found for {}", request.uri),
+                    None,
+                )),
+                Err(e) => Err(ErrorData::internal_error(
+                    format!("Error [{}]: {e}", e.code()),
+                    None,
+                )),
+            }
+        }
+        .instrument(self.session_logger.span())
+        .await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        async move {
+            let uri = LibraryUri::parse(&request.uri)
+                .map_err(|message| ErrorData::invalid_params(message, None))?;
+
+            // The current content is the baseline later refreshes are compared against.
+            let current = self
+                .backend
+                .fetch_library_documentation(&uri.library_id, uri.tokens, uri.topic.as_deref())
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Could not fetch {} for subscription: {e}", request.uri);
+                    None
+                });
+            if self
+                .subscriptions
+                .subscribe(&uri.to_string(), current.as_deref())
+            {
+                self.spawn_refresh(context.peer);
+            }
+            Ok(())
+        }
+        .instrument(self.session_logger.span())
+        .await
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let uri = LibraryUri::parse(&request.uri)
+            .map_err(|message| ErrorData::invalid_params(message, None))?;
+        self.subscriptions.unsubscribe(&uri.to_string());
+        Ok(())
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        self.session_logger.set_level(&context.peer, request.level);
+        Ok(())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        Ok(ListPromptsResult {
+            prompts: prompt_definitions(),
             next_cursor: None,
         })
     }
 
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        async move {
+            let prompt = DocsPrompt::parse(&request.name, request.arguments.as_ref())
+                .map_err(|message| ErrorData::invalid_params(message, None))?;
+
+            let mut messages = futures::future::try_join_all(
+                prompt
+                    .documents()
+                    .iter()
+                    .map(|document| self.embed_document(document)),
+            )
+            .await?;
+            messages.dedup();
+            messages.push(PromptMessage::new_text(
+                PromptMessageRole::User,
+                prompt.instructions(),
+            ));
+
+            Ok(GetPromptResult {
+                description: Some(prompt.description()),
+                messages,
+            })
+        }
+        .instrument(self.session_logger.span())
+        .await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, ErrorData> {
+        async move {
+            let value = request.argument.value.as_str();
+            let arguments = request
+                .context
+                .and_then(|context| context.arguments)
+                .unwrap_or_default();
+
+            let values = match request.argument.name.as_str() {
+                "context7CompatibleLibraryID" | "library" | "a" | "b" => {
+                    self.complete_library(value, true).await
+                }
+                "libraryName" => self.complete_library(value, false).await,
+                "topic" => {
+                    let library = arguments
+                        .get("context7CompatibleLibraryID")
+                        .or_else(|| arguments.get("library"))
+                        .cloned()
+                        .or_else(|| {
+                            Some(format!(
+                                "/{}/{}",
+                                arguments.get("org")?,
+                                arguments.get("project")?
+                            ))
+                        });
+                    matching_values(self.topics.topics(library.as_deref()), value)
+                }
+                // Variables of the `context7://{org}/{project}` resource template.
+                "org" | "project" => {
+                    let org = arguments.get("org");
+                    let segments = self.known_library_ids().await.into_iter().filter_map(|id| {
+                        let mut segments = id.trim_matches('/').split('/');
+                        let (id_org, project) = (segments.next()?, segments.next()?);
+                        match request.argument.name.as_str() {
+                            "org" => Some(id_org.to_string()),
+                            _ if org.is_none_or(|org| org == id_org) => Some(project.to_string()),
+                            _ => None,
+                        }
+                    });
+                    matching_values(segments, value)
+                }
+                _ => Vec::new(),
+            };
+
+            Ok(CompleteResult {
+                completion: completion_info(values),
+            })
+        }
+        .instrument(self.session_logger.span())
+        .await
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        match request.name.as_ref() {
-            "resolve-library-id" => {
-                let library_name = request
-                    .arguments
-                    .as_ref()
-                    .and_then(|args| args.get("libraryName"))
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ErrorData::invalid_request(
-                            "Missing libraryName parameter".to_string(),
-                            None,
-                        )
-                    })?;
-
-                match self.client.search_libraries(library_name).await {
-                    Ok(response) => {
-                        response.error.as_ref().map_or_else(|| {
-                            let results_text = format_search_results(&response);
-                            let text = format!(
-                                "Available Libraries (top matches):\n\nEach result includes:\n- Library ID: Context7-compatible identifier (format: /org/project)\n- Name: Library or package name\n- Description: Short summary\n- Code Snippets: Number of available code examples\n- Trust Score: Authority indicator\n- Versions: List of versions if available. Use one of those versions if the user provides a version in their query. The format of the version is /org/project/version.\n\nFor best results, select libraries based on name match, trust score, snippet coverage, and relevance to your use case.\n\n----------\n\n{results_text}"
-                            );
-                            Ok(CallToolResult::success(vec![Content::text(text)]))
-                        }, |error| Ok(CallToolResult::success(vec![Content::text(error)])))
-                    }
-                    Err(e) => {
-                        let text = format!(
-                            "Failed to retrieve library documentation data from Context7: {e}"
-                        );
-                        Ok(CallToolResult::success(vec![Content::text(text)]))
-                    }
+        let tool = request.name.to_string();
+        track_tool_call(&context, &tool, self.dispatch_tool(request, &context))
+            .instrument(self.session_logger.span())
+            .await
+    }
+}
+
+pub async fn run_server(tool: Context7Tool, transport: Transport) -> Result<()> {
+    match transport {
+        Transport::Stdio => {
+            eprintln!("Context7 Documentation MCP Server running on stdio");
+
+            let service = tool
+                .with_working_dir_roots(true)
+                .serve(transport::stdio())
+                .await?;
+            service.waiting().await?;
+        }
+        Transport::StreamableHttp(addr) => {
+            let router = streamable_http_router(tool);
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+
+            eprintln!(
+                "Context7 Documentation MCP Server running on http://{}/mcp",
+                listener.local_addr()?
+            );
+
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+        Transport::Sse(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            let addr = listener.local_addr()?;
+            let (router, ct) = sse_router(tool, addr);
+
+            eprintln!("Context7 Documentation MCP Server running on http://{addr}/sse");
+
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+            ct.cancel();
+        }
+        #[cfg(unix)]
+        Transport::Unix(path) => {
+            remove_stale_socket(&path)?;
+            let listener = bind_private_socket(&path)?;
+
+            eprintln!(
+                "Context7 Documentation MCP Server running on unix:{}",
+                path.display()
+            );
+
+            let result = tokio::select! {
+                result = serve_unix(tool, listener) => result,
+                () = shutdown_signal() => Ok(()),
+            };
+            let _ = std::fs::remove_file(&path);
+            result?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds the axum router serving `tool` over MCP Streamable HTTP at `/mcp`.
+///
+/// Requests from origins or hosts that `tool` does not allow are rejected.
+pub fn streamable_http_router(tool: Context7Tool) -> axum::Router {
+    let allowed = Arc::clone(&tool.allowed_origins);
+    let service = StreamableHttpService::new(
+        move || Ok(tool.for_session()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+    restrict_origins(axum::Router::new().nest_service("/mcp", service), allowed)
+}
+
+/// Builds the axum router serving `tool` over the legacy HTTP+SSE transport.
+///
+/// Each `GET /sse` connection gets its own session; cancel the returned token to
+/// close them all. Requests are restricted to allowed origins as for Streamable HTTP.
+pub fn sse_router(tool: Context7Tool, bind: SocketAddr) -> (axum::Router, CancellationToken) {
+    let allowed = Arc::clone(&tool.allowed_origins);
+    let (sse_server, router) = SseServer::new(SseServerConfig {
+        bind,
+        sse_path: "/sse".to_string(),
+        post_path: "/message".to_string(),
+        ct: CancellationToken::new(),
+        sse_keep_alive: None,
+    });
+    let ct = sse_server.with_service(move || tool.for_session());
+    (restrict_origins(router, allowed), ct)
+}
+
+/// Answers `403 Forbidden` to requests whose `Origin` or `Host` is not allowed.
+fn restrict_origins(router: axum::Router, allowed: Arc<AllowedOrigins>) -> axum::Router {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    router.layer(axum::middleware::from_fn(
+        move |request: axum::extract::Request, next: axum::middleware::Next| {
+            let permitted = is_allowed_request(&allowed, &request);
+            async move {
+                if permitted {
+                    next.run(request).await
+                } else {
+                    (StatusCode::FORBIDDEN, "Origin not allowed").into_response()
                 }
             }
-            "get-library-docs" => {
-                let library_id = request
-                    .arguments
-                    .as_ref()
-                    .and_then(|args| args.get("context7CompatibleLibraryID"))
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ErrorData::invalid_request(
-                            "Missing context7CompatibleLibraryID parameter".to_string(),
-                            None,
-                        )
-                    })?;
-
-                let topic = request
-                    .arguments
-                    .as_ref()
-                    .and_then(|args| args.get("topic"))
-                    .and_then(|v| v.as_str())
-                    .map(std::string::ToString::to_string);
-
-                let tokens = request
-                    .arguments
-                    .as_ref()
-                    .and_then(|args| args.get("tokens"))
-                    .and_then(rmcp::serde_json::Value::as_u64)
-                    .and_then(|t| u32::try_from(t).ok());
-
-                match self
-                    .client
-                    .fetch_library_documentation(library_id, tokens, topic.as_deref())
-                    .await
-                {
-                    Ok(Some(documentation)) => {
-                        Ok(CallToolResult::success(vec![Content::text(documentation)]))
-                    }
-                    Ok(None) => {
-                        let text = "Documentation not found or not finalized for this library. This might have happened because you used an invalid Context7-compatible library ID. To get a valid Context7-compatible library ID, use the 'resolve-library-id' with the package name you wish to retrieve documentation for.";
-                        Ok(CallToolResult::success(vec![Content::text(text)]))
-                    }
-                    Err(e) => {
-                        let text = format!("Error fetching library documentation: {e}");
-                        Ok(CallToolResult::success(vec![Content::text(text)]))
-                    }
+        },
+    ))
+}
+
+fn is_allowed_request(allowed: &AllowedOrigins, request: &axum::extract::Request) -> bool {
+    use axum::http::header;
+
+    // Unreadable values count as present, so they are rejected.
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap_or_default())
+    };
+    let origin = header(header::ORIGIN);
+    let host = header(header::HOST).or_else(|| request.uri().host());
+    let permitted = allowed.allows(origin, host);
+    if !permitted {
+        tracing::warn!(
+            "Rejected request from origin {origin:?} for host {host:?}; see --allowed-origin"
+        );
+    }
+    permitted
+}
+
+/// Accepts connections on `listener` forever, serving each with its own session.
+///
+/// Sessions are clones of `tool` and therefore share its backend.
+#[cfg(unix)]
+pub async fn serve_unix(tool: Context7Tool, listener: tokio::net::UnixListener) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let tool = tool.for_session();
+        tokio::spawn(async move {
+            match tool.serve(stream).await {
+                Ok(service) => {
+                    let _ = service.waiting().await;
                 }
+                Err(e) => tracing::warn!("Unix socket session failed to initialize: {e}"),
+            }
+        });
+    }
+}
+
+/// Removes a socket left behind by a previous run, refusing to touch anything else.
+///
+/// A socket is only stale if nothing accepts connections on it; one that a running
+/// server still listens on is reported as in use.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            match std::os::unix::net::UnixStream::connect(path) {
+                Ok(_) => anyhow::bail!("{}: address in use", path.display()),
+                Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                    Ok(std::fs::remove_file(path)?)
+                }
+                Err(e) => anyhow::bail!("{}: {e}", path.display()),
             }
-            _ => Err(ErrorData::invalid_request(
-                format!("Unknown tool: {}", request.name),
-                None,
-            )),
         }
+        Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+        Err(_) => Ok(()),
     }
 }
 
-pub async fn run_server(api_key: Option<String>, insecure: bool) -> Result<()> {
-    let tool = Context7Tool::new(api_key, insecure);
+/// Binds a Unix socket at `path` that only the current user can connect to.
+///
+/// Anyone who can connect can use the server's API key. The socket is therefore
+/// bound inside a private directory and only moved to `path` once its mode is
+/// 0600, so there is no moment when others can reach it.
+#[cfg(unix)]
+fn bind_private_socket(path: &std::path::Path) -> Result<tokio::net::UnixListener> {
+    use anyhow::Context;
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
 
-    eprintln!("Context7 Documentation MCP Server running on stdio");
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let staging = parent.join(format!(".c67-mcp-{}.tmp", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("creating {}", staging.display()))?;
 
-    let service = tool.serve(transport::stdio()).await?;
-    service.waiting().await?;
-    Ok(())
+    let bind = || -> Result<tokio::net::UnixListener> {
+        let staged = staging.join("socket");
+        let listener = tokio::net::UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting permissions of {}", path.display()))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    };
+    let result = bind();
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
 }
@@ -1,7 +1,39 @@
 #[cfg(test)]
 mod tests {
-    use crate::Context7Tool;
+    use crate::{
+        Context7Client, Context7Error, Context7Tool, DocsBackend, McpLogger, ResponseLimit,
+        SearchResponse, SearchResult, TokenCounter, sse_router, streamable_http_router,
+    };
+    use async_trait::async_trait;
+    use rmcp::handler::client::ClientHandler;
     use rmcp::handler::server::ServerHandler;
+    use rmcp::model::{
+        CallToolRequestParam, ClientCapabilities, ClientInfo, CompletionContext, ErrorData,
+        GetPromptRequestParam, ListRootsResult, LoggingLevel, LoggingMessageNotificationParam,
+        PromptMessageContent, ReadResourceRequestParam, ResourceContents,
+        ResourceUpdatedNotificationParam, Root, RootsCapabilities, SetLevelRequestParam,
+        SubscribeRequestParam,
+    };
+    use rmcp::service::{NotificationContext, RequestContext, RunningService};
+    use rmcp::{RoleClient, ServiceExt};
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tracing_subscriber::layer::SubscriberExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Connects an in-process MCP client to `tool` over an in-memory pipe.
+    async fn connect(tool: Context7Tool) -> RunningService<RoleClient, ()> {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        ().serve(client_io).await.unwrap()
+    }
 
     #[tokio::test]
     async fn test_server_initialization() {
@@ -49,4 +81,1064 @@ mod tests {
         let info_key = server_insecure_with_key.get_info();
         assert_eq!(info_key.server_info.name, "c67-mcp");
     }
+
+    #[tokio::test]
+    async fn test_streamable_http_assigns_session_id() {
+        let client = Arc::new(Context7Client::new(None, false));
+        let router = streamable_http_router(Context7Tool::with_client(client));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = tokio::task::spawn_blocking(move || {
+            ureq::post(&format!("http://{addr}/mcp"))
+                .header("Accept", "application/json, text/event-stream")
+                .send_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "initialize",
+                    "params": {
+                        "protocolVersion": "2025-03-26",
+                        "capabilities": {},
+                        "clientInfo": { "name": "test", "version": "0.0.0" }
+                    }
+                }))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert!(response.headers().contains_key("mcp-session-id"));
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_rejects_foreign_origins() {
+        let client = Arc::new(Context7Client::new(None, false));
+        let router = streamable_http_router(Context7Tool::with_client(client));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let status = |header: &'static str, value: &'static str| {
+            tokio::task::spawn_blocking(move || {
+                ureq::get(&format!("http://{addr}/mcp"))
+                    .header(header, value)
+                    .config()
+                    .http_status_as_error(false)
+                    .build()
+                    .call()
+                    .unwrap()
+                    .status()
+            })
+        };
+
+        assert_eq!(
+            status("Origin", "https://attacker.example").await.unwrap(),
+            403
+        );
+        assert_eq!(status("Host", "attacker.example").await.unwrap(), 403);
+        assert_ne!(
+            status("Origin", "http://localhost:3000").await.unwrap(),
+            403
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_announces_message_endpoint() {
+        let client = Arc::new(Context7Client::new(None, false));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (router, ct) = sse_router(Context7Tool::with_client(client), addr);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let first_event = tokio::task::spawn_blocking(move || {
+            use std::io::Read;
+
+            let mut response = ureq::get(&format!("http://{addr}/sse")).call().unwrap();
+            let mut buf = [0u8; 256];
+            let n = response.body_mut().as_reader().read(&mut buf).unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        })
+        .await
+        .unwrap();
+        ct.cancel();
+
+        assert!(first_event.contains("event: endpoint"));
+        assert!(first_event.contains("/message?sessionId="));
+    }
+
+    #[tokio::test]
+    async fn test_sse_rejects_foreign_origins() {
+        let client = Arc::new(Context7Client::new(None, false));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (router, ct) = sse_router(Context7Tool::with_client(client), addr);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let status = |path: &'static str, header: &'static str| {
+            tokio::task::spawn_blocking(move || {
+                ureq::post(&format!("http://{addr}{path}"))
+                    .header(header, "attacker.example")
+                    .config()
+                    .http_status_as_error(false)
+                    .build()
+                    .send_empty()
+                    .unwrap()
+                    .status()
+            })
+        };
+
+        assert_eq!(status("/message", "Host").await.unwrap(), 403);
+        assert_eq!(status("/sse", "Origin").await.unwrap(), 403);
+        ct.cancel();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_serves_one_session_per_connection() {
+        use crate::serve_unix;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let path = std::env::temp_dir().join(format!("c67-mcp-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let client = Arc::new(Context7Client::new(None, false));
+        tokio::spawn(serve_unix(Context7Tool::with_client(client), listener));
+
+        for _ in 0..2 {
+            let stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+            let (read, mut write) = stream.into_split();
+            write
+                .write_all(
+                    concat!(
+                        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":"#,
+                        r#"{"protocolVersion":"2025-03-26","capabilities":{},"#,
+                        r#""clientInfo":{"name":"test","version":"0.0.0"}}}"#,
+                        "\n"
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+
+            let mut line = String::new();
+            BufReader::new(read).read_line(&mut line).await.unwrap();
+            assert!(line.contains(r#""name":"c67-mcp""#));
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_is_private_to_owner() {
+        use crate::{Transport, run_server};
+        use std::os::unix::fs::PermissionsExt;
+
+        let path =
+            std::env::temp_dir().join(format!("c67-mcp-test-private-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let client = Arc::new(Context7Client::new(None, false));
+        let server = tokio::spawn(run_server(
+            Context7Tool::with_client(client),
+            Transport::Unix(path.clone()),
+        ));
+
+        let mut connected = false;
+        for _ in 0..100 {
+            if tokio::net::UnixStream::connect(&path).await.is_ok() {
+                connected = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(connected);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let staging = path.with_file_name(format!(".c67-mcp-{}.tmp", std::process::id()));
+        assert!(!staging.exists());
+
+        server.abort();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_in_use_is_not_replaced() {
+        use crate::{Transport, run_server};
+
+        let path =
+            std::env::temp_dir().join(format!("c67-mcp-test-in-use-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let client = Arc::new(Context7Client::new(None, false));
+
+        let error = run_server(
+            Context7Tool::with_client(client),
+            Transport::Unix(path.clone()),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.to_string().contains("address in use"));
+        assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_reports_client_errors_as_tool_errors() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/missing/lib"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false);
+        let mcp = connect(Context7Tool::with_client(Arc::new(client))).await;
+
+        let result = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({ "context7CompatibleLibraryID": "/missing/lib" })
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.is_error, Some(true));
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Error [not_found]:"));
+    }
+
+    struct StaticBackend;
+
+    #[async_trait]
+    impl DocsBackend for StaticBackend {
+        async fn search_libraries(&self, query: &str) -> Result<SearchResponse, Context7Error> {
+            Ok(SearchResponse {
+                results: vec![SearchResult {
+                    id: format!("/static/{query}"),
+                    title: query.to_string(),
+                    description: "Served by a custom backend".to_string(),
+                    total_snippets: None,
+                    trust_score: None,
+                    versions: None,
+                    origin: None,
+                }],
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            library_id: &str,
+            _tokens: Option<u32>,
+            topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            Ok(Some(format!("# {library_id} {}", topic.unwrap_or("all"))))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_serves_injected_backend() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+
+        let tools = mcp.list_all_tools().await.unwrap();
+        let output_schema = tools[0].output_schema.as_ref().unwrap();
+        assert_eq!(output_schema["type"], "object");
+        assert!(output_schema["properties"]["bestMatch"].is_object());
+
+        let search = mcp
+            .call_tool(CallToolRequestParam {
+                name: "resolve-library-id".into(),
+                arguments: json!({ "libraryName": "widgets" }).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        assert!(
+            search.content[0]
+                .as_text()
+                .unwrap()
+                .text
+                .contains("/static/widgets")
+        );
+
+        let docs = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({
+                    "context7CompatibleLibraryID": "/static/widgets",
+                    "topic": "setup"
+                })
+                .as_object()
+                .cloned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            docs.content[0].as_text().unwrap().text,
+            "# /static/widgets setup"
+        );
+
+        let error = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({
+                    "context7CompatibleLibraryID": "/static/widgets",
+                    "tokens": -1
+                })
+                .as_object()
+                .cloned(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("tokens: invalid value"));
+    }
+
+    struct RootsClient {
+        root: String,
+    }
+
+    impl ClientHandler for RootsClient {
+        fn get_info(&self) -> ClientInfo {
+            ClientInfo {
+                capabilities: ClientCapabilities {
+                    roots: Some(RootsCapabilities::default()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        async fn list_roots(
+            &self,
+            _context: RequestContext<RoleClient>,
+        ) -> Result<ListRootsResult, ErrorData> {
+            Ok(ListRootsResult {
+                roots: vec![Root {
+                    uri: self.root.clone(),
+                    name: None,
+                }],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_project_libraries_reads_client_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[dependencies]\nwidgets = \"2\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.lock"),
+            "[[package]]\nname = \"widgets\"\nversion = \"2.4.0\"\n",
+        )
+        .unwrap();
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let tool = Context7Tool::with_backend(Arc::new(StaticBackend));
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        let client = RootsClient {
+            root: format!("file://{}", dir.path().display()),
+        };
+        let mcp = client.serve(client_io).await.unwrap();
+
+        let result = mcp
+            .call_tool(CallToolRequestParam {
+                name: "list-project-libraries".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.contains(&dir.path().display().to_string()));
+        assert!(text.contains("- widgets 2.4.0 (Rust, Cargo.toml): /static/widgets"));
+    }
+
+    #[tokio::test]
+    async fn test_list_project_libraries_without_roots_ignores_working_dir() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+
+        let result = mcp
+            .call_tool(CallToolRequestParam {
+                name: "list-project-libraries".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.is_error, Some(true));
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Error [no_roots]:"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_library_id_selects_requested_version() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [{
+                    "id": "/vercel/next.js",
+                    "title": "Next.js",
+                    "description": "The React Framework",
+                    "versions": ["v14.3.0-canary.87", "v14.2.0", "v13.5.6"]
+                }]
+            })))
+            .mount(&mock_server)
+            .await;
+        let client = Arc::new(Context7Client::new_with_base_url(
+            None,
+            mock_server.uri(),
+            false,
+        ));
+        let mcp = connect(Context7Tool::with_client(client)).await;
+
+        let resolve = |version: &str| {
+            mcp.call_tool(CallToolRequestParam {
+                name: "resolve-library-id".into(),
+                arguments: json!({ "libraryName": "next.js", "version": version })
+                    .as_object()
+                    .cloned(),
+            })
+        };
+
+        let result = resolve("^14.1").await.unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Best match for version ^14.1: /vercel/next.js/v14.2.0"));
+        assert!(text.contains("Available Libraries"));
+        let structured = result.structured_content.unwrap();
+        assert_eq!(
+            structured["bestMatch"]["libraryId"],
+            "/vercel/next.js/v14.2.0"
+        );
+        assert_eq!(structured["results"][0]["id"], "/vercel/next.js");
+
+        let result = resolve("12").await.unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with(
+            "Note: Next.js has no documentation matching version 12. The nearest available version is v13.5.6: /vercel/next.js/v13.5.6"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_multiple_library_docs_returns_one_block_per_library() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+
+        let result = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-multiple-library-docs".into(),
+                arguments: json!({
+                    "libraries": [
+                        { "libraryId": "/static/axum", "topic": "routing" },
+                        { "libraryId": "/static/sqlx" },
+                        { "libraryId": "/static/insta", "tokens": 2000 }
+                    ]
+                })
+                .as_object()
+                .cloned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(false));
+        let blocks: Vec<&str> = result
+            .content
+            .iter()
+            .map(|content| content.as_text().unwrap().text.as_str())
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                "## /static/axum (topic: routing)\n\n# /static/axum routing",
+                "## /static/sqlx\n\n# /static/sqlx all",
+                "## /static/insta\n\n# /static/insta all",
+            ]
+        );
+
+        let error = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-multiple-library-docs".into(),
+                arguments: json!({ "libraries": [] }).as_object().cloned(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("at least one library"));
+    }
+
+    #[tokio::test]
+    async fn test_library_docs_are_readable_resources() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+
+        let templates = mcp.list_resource_templates(None).await.unwrap();
+        assert_eq!(
+            templates.resource_templates[0].uri_template,
+            "context7://{org}/{project}{/version}{?topic,tokens}"
+        );
+        assert!(mcp.list_resources(None).await.unwrap().resources.is_empty());
+
+        let result = mcp
+            .read_resource(ReadResourceRequestParam {
+                uri: "context7://static/widgets?topic=setup".to_string(),
+            })
+            .await
+            .unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(text, "# /static/widgets setup");
+
+        let resources = mcp.list_resources(None).await.unwrap().resources;
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "context7://static/widgets?topic=setup");
+        assert_eq!(resources[0].name, "/static/widgets (setup)");
+
+        let error = mcp
+            .read_resource(ReadResourceRequestParam {
+                uri: "context7://widgets".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("org/project"));
+    }
+
+    /// Returns a new revision of the documentation on every fetch.
+    #[derive(Default)]
+    struct ChangingBackend {
+        revision: AtomicU32,
+    }
+
+    #[async_trait]
+    impl DocsBackend for ChangingBackend {
+        async fn search_libraries(&self, _query: &str) -> Result<SearchResponse, Context7Error> {
+            Ok(SearchResponse {
+                results: Vec::new(),
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            let revision = self.revision.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(format!("# {library_id} revision {revision}")))
+        }
+    }
+
+    struct UpdatesClient {
+        updates: mpsc::UnboundedSender<String>,
+    }
+
+    impl ClientHandler for UpdatesClient {
+        async fn on_resource_updated(
+            &self,
+            params: ResourceUpdatedNotificationParam,
+            _context: NotificationContext<RoleClient>,
+        ) {
+            let _ = self.updates.send(params.uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribed_resources_notify_on_change() {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let tool = Context7Tool::with_backend(Arc::new(ChangingBackend::default()))
+                .with_refresh_interval(Duration::from_millis(20));
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        let (updates, mut received) = mpsc::unbounded_channel();
+        let mcp = UpdatesClient { updates }.serve(client_io).await.unwrap();
+
+        assert_eq!(
+            mcp.peer_info()
+                .and_then(|info| info.capabilities.resources.as_ref())
+                .and_then(|resources| resources.subscribe),
+            Some(true)
+        );
+
+        mcp.subscribe(SubscribeRequestParam {
+            uri: "context7://static/widgets".to_string(),
+        })
+        .await
+        .unwrap();
+        let uri = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(uri, "context7://static/widgets");
+
+        let error = mcp
+            .subscribe(SubscribeRequestParam {
+                uri: "context7://widgets".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("org/project"));
+    }
+
+    #[tokio::test]
+    async fn test_prompts_embed_resolved_documentation() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+
+        let prompts = mcp.list_prompts(None).await.unwrap().prompts;
+        let names: Vec<&str> = prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["explain-api", "migration-guide", "compare-libraries"]
+        );
+
+        let result = mcp
+            .get_prompt(GetPromptRequestParam {
+                name: "compare-libraries".to_string(),
+                arguments: json!({ "a": "axum", "b": "/static/warp" })
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.messages.len(), 3);
+        let PromptMessageContent::Resource { resource } = &result.messages[0].content else {
+            panic!("expected an embedded resource");
+        };
+        let ResourceContents::TextResourceContents { uri, text, .. } = &resource.resource else {
+            panic!("expected text contents");
+        };
+        assert_eq!(uri, "context7://static/axum");
+        assert_eq!(text, "# /static/axum all");
+        let PromptMessageContent::Text { text } = &result.messages[2].content else {
+            panic!("expected instructions");
+        };
+        assert!(text.contains("compare axum and /static/warp"));
+
+        let error = mcp
+            .get_prompt(GetPromptRequestParam {
+                name: "explain-api".to_string(),
+                arguments: json!({ "library": "axum" }).as_object().cloned(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("'topic'"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_suggests_libraries_and_topics() {
+        let mcp = connect(Context7Tool::with_backend(Arc::new(StaticBackend))).await;
+        assert!(
+            mcp.peer_info()
+                .is_some_and(|info| info.capabilities.completions.is_some())
+        );
+
+        mcp.read_resource(ReadResourceRequestParam {
+            uri: "context7://static/widgets?topic=setup".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let libraries = mcp
+            .complete_prompt_simple("explain-api", "library", "wid")
+            .await
+            .unwrap();
+        assert_eq!(libraries, ["/static/widgets", "/static/wid"]);
+
+        let context = CompletionContext {
+            arguments: Some([("library".to_string(), "/static/widgets".to_string())].into()),
+        };
+        let topics = mcp
+            .complete_prompt_argument("explain-api", "topic", "set", Some(context))
+            .await
+            .unwrap();
+        assert_eq!(topics.values, ["/static/widgets setup"]);
+
+        let orgs = mcp
+            .complete_resource_simple("context7://{org}/{project}", "org", "st")
+            .await
+            .unwrap();
+        assert_eq!(orgs, ["static"]);
+    }
+
+    #[tokio::test]
+    async fn test_complete_does_not_retry_failed_searches() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/search"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Context7Client::new_with_base_url(None, mock_server.uri(), false);
+        let mcp = connect(Context7Tool::with_client(Arc::new(client))).await;
+
+        let libraries = mcp
+            .complete_prompt_simple("explain-api", "library", "wid")
+            .await
+            .unwrap();
+        assert!(libraries.is_empty());
+    }
+
+    /// Never finishes a fetch, and reports when an abandoned fetch is dropped.
+    struct StalledBackend {
+        dropped: mpsc::UnboundedSender<()>,
+    }
+
+    struct NotifyOnDrop(mpsc::UnboundedSender<()>);
+
+    impl Drop for NotifyOnDrop {
+        fn drop(&mut self) {
+            let _ = self.0.send(());
+        }
+    }
+
+    #[async_trait]
+    impl DocsBackend for StalledBackend {
+        async fn search_libraries(&self, _query: &str) -> Result<SearchResponse, Context7Error> {
+            std::future::pending().await
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            _library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            let _notify = NotifyOnDrop(self.dropped.clone());
+            std::future::pending().await
+        }
+    }
+
+    async fn next_message(
+        lines: &mut tokio::io::Lines<
+            tokio::io::BufReader<tokio::io::ReadHalf<tokio::io::DuplexStream>>,
+        >,
+    ) -> serde_json::Value {
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_report_progress_and_stop_when_cancelled() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let (dropped, mut fetch_dropped) = mpsc::unbounded_channel();
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let tool = Context7Tool::with_backend(Arc::new(StalledBackend { dropped }));
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        let (read, mut write) = tokio::io::split(client_io);
+        let mut lines = BufReader::new(read).lines();
+
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "0.0.0"},
+            }}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "get-library-docs",
+                "arguments": {"context7CompatibleLibraryID": "/static/widgets"},
+                "_meta": {"progressToken": "call-2"},
+            }}),
+        ] {
+            write
+                .write_all(format!("{message}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+        assert_eq!(next_message(&mut lines).await["id"], 1);
+
+        let update = next_message(&mut lines).await;
+        assert_eq!(update["method"], "notifications/progress");
+        assert_eq!(update["params"]["progressToken"], "call-2");
+        assert!(update["params"]["progress"].as_f64().unwrap() > 0.0);
+        assert!(
+            update["params"]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("get-library-docs running")
+        );
+
+        let cancel = json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {
+            "requestId": 2,
+            "reason": "user aborted",
+        }});
+        write
+            .write_all(format!("{cancel}\n").as_bytes())
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), fetch_dropped.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // MCP forbids answering a cancelled request, and its progress stops with it.
+        while let Ok(line) =
+            tokio::time::timeout(Duration::from_millis(200), lines.next_line()).await
+        {
+            let message: serde_json::Value = serde_json::from_str(&line.unwrap().unwrap()).unwrap();
+            assert_eq!(message["method"], "notifications/progress");
+        }
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let ping = json!({"jsonrpc": "2.0", "id": 3, "method": "ping"});
+        write
+            .write_all(format!("{ping}\n").as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(next_message(&mut lines).await["id"], 3);
+    }
+
+    struct LoggingClient {
+        messages: mpsc::UnboundedSender<LoggingMessageNotificationParam>,
+    }
+
+    impl ClientHandler for LoggingClient {
+        async fn on_logging_message(
+            &self,
+            params: LoggingMessageNotificationParam,
+            _context: NotificationContext<RoleClient>,
+        ) {
+            let _ = self.messages.send(params);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_level_forwards_tracing_events() {
+        let logger = McpLogger::new();
+        let _subscriber =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(logger.layer()));
+        // Dependencies' callsites stay disabled rather than being filtered per event.
+        assert!(!tracing::enabled!(target: "hyper::proto", tracing::Level::TRACE));
+        assert!(tracing::enabled!(target: "c67_mcp::client", tracing::Level::DEBUG));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/vercel/next.js"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# Next.js"))
+            .mount(&mock_server)
+            .await;
+        let client = Arc::new(Context7Client::new_with_base_url(
+            None,
+            mock_server.uri(),
+            false,
+        ));
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let tool = Context7Tool::with_client(client).with_logger(logger);
+        tokio::spawn(async move {
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        let (messages, mut received) = mpsc::unbounded_channel();
+        let mcp = LoggingClient { messages }.serve(client_io).await.unwrap();
+        assert!(
+            mcp.peer_info()
+                .is_some_and(|info| info.capabilities.logging.is_some())
+        );
+
+        let fetch_docs = || {
+            mcp.call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({ "context7CompatibleLibraryID": "/vercel/next.js" })
+                    .as_object()
+                    .cloned(),
+            })
+        };
+
+        mcp.set_level(SetLevelRequestParam {
+            level: LoggingLevel::Warning,
+        })
+        .await
+        .unwrap();
+        fetch_docs().await.unwrap();
+
+        mcp.set_level(SetLevelRequestParam {
+            level: LoggingLevel::Debug,
+        })
+        .await
+        .unwrap();
+        fetch_docs().await.unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message.level, LoggingLevel::Debug));
+        assert_eq!(message.logger.as_deref(), Some("c67_mcp::client"));
+        let text = message.data.as_str().unwrap();
+        assert!(text.starts_with("GET "), "{text}");
+        assert!(
+            text.ends_with("/v1/vercel/next.js returned HTTP 200"),
+            "{text}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_events_only_reach_their_own_session() {
+        let logger = McpLogger::new();
+        let _subscriber =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(logger.layer()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/vercel/next.js"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# Next.js"))
+            .mount(&mock_server)
+            .await;
+        let client = Arc::new(Context7Client::new_with_base_url(
+            None,
+            mock_server.uri(),
+            false,
+        ));
+        let tool = Context7Tool::with_client(client).with_logger(logger);
+
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+            let session = tool.for_session();
+            tokio::spawn(async move {
+                let server = session.serve(server_io).await.unwrap();
+                let _ = server.waiting().await;
+            });
+            let (messages, received) = mpsc::unbounded_channel();
+            let mcp = LoggingClient { messages }.serve(client_io).await.unwrap();
+            mcp.set_level(SetLevelRequestParam {
+                level: LoggingLevel::Debug,
+            })
+            .await
+            .unwrap();
+            sessions.push((mcp, received));
+        }
+        let (_idle, mut idle_log) = sessions.pop().unwrap();
+        let (fetching, mut fetching_log) = sessions.pop().unwrap();
+
+        fetching
+            .call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({ "context7CompatibleLibraryID": "/vercel/next.js" })
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), fetching_log.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            message
+                .data
+                .as_str()
+                .unwrap()
+                .contains("/v1/vercel/next.js")
+        );
+        let leaked = tokio::time::timeout(Duration::from_millis(200), idle_log.recv()).await;
+        assert!(leaked.is_err(), "other session received {leaked:?}");
+    }
+
+    struct LongBackend;
+
+    #[async_trait]
+    impl DocsBackend for LongBackend {
+        async fn search_libraries(&self, _query: &str) -> Result<SearchResponse, Context7Error> {
+            Ok(SearchResponse {
+                results: Vec::new(),
+                error: None,
+            })
+        }
+
+        async fn fetch_library_documentation(
+            &self,
+            _library_id: &str,
+            _tokens: Option<u32>,
+            _topic: Option<&str>,
+        ) -> Result<Option<String>, Context7Error> {
+            Ok(Some(
+                (1..=10)
+                    .map(|n| format!("## Part {n}\n\n{}\n\n", "text ".repeat(50)))
+                    .collect(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_documentation_is_capped_at_response_limit() {
+        let tool =
+            Context7Tool::with_backend(Arc::new(LongBackend)).with_response_limit(ResponseLimit {
+                max_tokens: 200,
+                counter: TokenCounter::Bytes,
+            });
+        let mcp = connect(tool).await;
+
+        let docs = mcp
+            .call_tool(CallToolRequestParam {
+                name: "get-library-docs".into(),
+                arguments: json!({ "context7CompatibleLibraryID": "/long/docs" })
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+        let text = &docs.content[0].as_text().unwrap().text;
+        assert!(text.contains("## Part 2"));
+        assert!(!text.contains("## Part 3"));
+        assert!(text.ends_with("[... truncated, 8 more sections available]"));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_subscriptions_do_not_report_changes() {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let tool = Context7Tool::with_backend(Arc::new(LongBackend))
+                .with_refresh_interval(Duration::from_millis(20))
+                .with_response_limit(ResponseLimit {
+                    max_tokens: 200,
+                    counter: TokenCounter::Bytes,
+                });
+            let server = tool.serve(server_io).await.unwrap();
+            let _ = server.waiting().await;
+        });
+        let (updates, mut received) = mpsc::unbounded_channel();
+        let mcp = UpdatesClient { updates }.serve(client_io).await.unwrap();
+
+        mcp.subscribe(SubscribeRequestParam {
+            uri: "context7://long/docs".to_string(),
+        })
+        .await
+        .unwrap();
+        let read = mcp
+            .read_resource(ReadResourceRequestParam {
+                uri: "context7://long/docs".to_string(),
+            })
+            .await
+            .unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &read.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert!(text.ends_with("[... truncated, 8 more sections available]"));
+
+        // Several refreshes of unchanged documentation must not look like updates.
+        let update = tokio::time::timeout(Duration::from_millis(200), received.recv()).await;
+        assert!(update.is_err(), "unexpected update: {update:?}");
+    }
 }
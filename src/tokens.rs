@@ -0,0 +1,146 @@
+/// How tokens are counted locally, approximating the model's tokenizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenCounter {
+    /// About four bytes per token, typical for English prose and code.
+    #[default]
+    Bytes,
+    /// About four tokens per three whitespace-separated words.
+    Words,
+}
+
+impl TokenCounter {
+    /// Parses a counter name as given to `--tokenizer`.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "bytes" => Ok(Self::Bytes),
+            "words" => Ok(Self::Words),
+            _ => Err(format!("expected 'bytes' or 'words', got '{name}'")),
+        }
+    }
+
+    #[must_use]
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            Self::Bytes => estimate_tokens(text),
+            Self::Words => (text.split_whitespace().count() * 4).div_ceil(3),
+        }
+    }
+}
+
+/// Rough token count: about four bytes of English text per token.
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// A hard cap on the size of documentation returned to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseLimit {
+    pub max_tokens: usize,
+    pub counter: TokenCounter,
+}
+
+impl ResponseLimit {
+    /// Cuts `text` down to the cap on section or code-block boundaries, ending
+    /// with a marker saying how many sections were left out; `None` when it fits.
+    #[must_use]
+    pub fn truncate(&self, text: &str) -> Option<String> {
+        if self.counter.count(text) <= self.max_tokens {
+            return None;
+        }
+
+        let sections = split_sections(text);
+        let marker =
+            |remaining: usize| format!("\n\n[... truncated, {remaining} more sections available]");
+        let budget = self
+            .max_tokens
+            .saturating_sub(self.counter.count(&marker(sections.len())));
+
+        let mut used = 0;
+        let mut kept = 0;
+        for section in &sections {
+            let cost = self.counter.count(section);
+            if used + cost > budget {
+                break;
+            }
+            used += cost;
+            kept += 1;
+        }
+
+        let head = if kept == 0 {
+            // Not even the first section fits: keep as much of it as does, still
+            // counting it as available.
+            truncate_section(sections[0], budget, self.counter)
+        } else {
+            sections[..kept].concat()
+        };
+        Some(format!(
+            "{}{}",
+            head.trim_end(),
+            marker(sections.len() - kept)
+        ))
+    }
+}
+
+/// Whether `line` opens or closes a fenced code block.
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// Splits documentation before each Markdown heading or separator line
+/// (such as the dashes between Context7 snippets) outside code blocks.
+fn split_sections(text: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut in_code = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if is_fence(line) {
+            in_code = !in_code;
+        } else if !in_code && offset > start {
+            let level = trimmed.chars().take_while(|&c| c == '#').count();
+            let is_heading = (1..=6).contains(&level) && trimmed[level..].starts_with(' ');
+            let is_separator = trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-');
+            if is_heading || is_separator {
+                sections.push(&text[start..offset]);
+                start = offset;
+            }
+        }
+        offset += line.len();
+    }
+    sections.push(&text[start..]);
+    sections
+}
+
+/// The longest prefix of `section` within `budget`, cut at a blank line outside
+/// code blocks where possible and otherwise at a line, closing any open fence.
+fn truncate_section(section: &str, budget: usize, counter: TokenCounter) -> String {
+    let mut in_code = false;
+    let mut block_end = 0;
+    let mut line_in_code = false;
+    let mut offset = 0;
+    let mut used = 0;
+    for line in section.split_inclusive('\n') {
+        used += counter.count(line);
+        if used > budget {
+            break;
+        }
+        offset += line.len();
+        if is_fence(line) {
+            in_code = !in_code;
+        }
+        if !in_code && line.trim().is_empty() {
+            block_end = offset;
+        }
+        line_in_code = in_code;
+    }
+
+    if block_end > 0 {
+        section[..block_end].to_string()
+    } else if line_in_code {
+        format!("{}\n```", section[..offset].trim_end())
+    } else {
+        section[..offset].to_string()
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ResponseLimit, TokenCounter};
+
+    fn limit(max_tokens: usize) -> ResponseLimit {
+        ResponseLimit {
+            max_tokens,
+            counter: TokenCounter::Bytes,
+        }
+    }
+
+    #[test]
+    fn test_token_counters() {
+        assert_eq!(TokenCounter::parse("bytes"), Ok(TokenCounter::Bytes));
+        assert_eq!(TokenCounter::parse("words"), Ok(TokenCounter::Words));
+        assert!(TokenCounter::parse("tiktoken").is_err());
+
+        assert_eq!(TokenCounter::Bytes.count("abcdefghi"), 3);
+        assert_eq!(TokenCounter::Words.count("one two three"), 4);
+        assert_eq!(TokenCounter::Words.count(""), 0);
+    }
+
+    #[test]
+    fn test_text_within_limit_is_untouched() {
+        assert_eq!(limit(100).truncate("# Title\n\nShort."), None);
+    }
+
+    #[test]
+    fn test_truncates_on_section_boundaries() {
+        let section = |n: usize| format!("## Section {n}\n\n{}\n\n", "word ".repeat(40));
+        let text: String = (1..=5).map(section).collect();
+
+        let truncated = limit(150).truncate(&text).unwrap();
+        assert!(truncated.starts_with(&section(1)));
+        assert!(truncated.contains("## Section 2"));
+        assert!(!truncated.contains("## Section 3"));
+        assert!(truncated.ends_with("[... truncated, 3 more sections available]"));
+        assert!(TokenCounter::Bytes.count(&truncated) <= 150);
+    }
+
+    #[test]
+    fn test_splits_on_separators_outside_code() {
+        let snippet = |n: usize| {
+            format!(
+                "TITLE: Snippet {n}\n\n```\n----\n{}\n```\n\n----------------------------------------\n\n",
+                "code ".repeat(30)
+            )
+        };
+        let text: String = (1..=4).map(snippet).collect();
+
+        let truncated = limit(100).truncate(&text).unwrap();
+        assert!(truncated.contains("TITLE: Snippet 1"));
+        assert!(!truncated.contains("TITLE: Snippet 2"));
+        // The dashes inside the code block do not start a section.
+        assert!(truncated.ends_with("[... truncated, 4 more sections available]"));
+    }
+
+    #[test]
+    fn test_oversized_section_is_cut_inside_and_fences_closed() {
+        let text = format!(
+            "# Guide\n\nIntro paragraph.\n\n```rust\n{}```\n",
+            "let x = 1;\n".repeat(100)
+        );
+
+        let truncated = limit(60).truncate(&text).unwrap();
+        assert_eq!(
+            truncated,
+            "# Guide\n\nIntro paragraph.\n\n[... truncated, 1 more sections available]"
+        );
+
+        let code = format!("```rust\n{}```\n", "let x = 1;\n".repeat(100));
+        let truncated = limit(60).truncate(&code).unwrap();
+        assert!(truncated.starts_with("```rust\nlet x = 1;\n"));
+        assert!(
+            truncated.ends_with("let x = 1;\n```\n\n[... truncated, 1 more sections available]")
+        );
+        assert!(TokenCounter::Bytes.count(&truncated) <= 60);
+    }
+}
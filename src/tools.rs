@@ -0,0 +1,174 @@
+use rmcp::model::JsonObject;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+
+use crate::budget::{TokenShare, split_token_budget};
+use crate::client::{SearchResponse, SearchResult};
+use crate::version::{VersionMatch, select_version};
+
+/// Arguments of `resolve-library-id`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveLibraryArgs {
+    /// Library name to search for and retrieve a Context7-compatible library ID.
+    pub library_name: String,
+    /// Optional version or semver range of the library (e.g., '14', '^18.2', '>=1.0, <2', '1.2 - 1.4', '1.x'). The best matching '/org/project/version' ID is selected; when no published version matches, the nearest one is used and noted.
+    pub version: Option<String>,
+}
+
+impl ResolveLibraryArgs {
+    /// The requested version, ignoring blank input.
+    #[must_use]
+    pub fn version(&self) -> Option<&str> {
+        self.version
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// Arguments of `get-library-docs`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct GetLibraryDocsArgs {
+    /// Exact Context7-compatible library ID (e.g., '/mongodb/docs', '/vercel/next.js', '/supabase/supabase', '/vercel/next.js/v14.3.0-canary.87') retrieved from 'resolve-library-id' or directly from user query in the format '/org/project' or '/org/project/version'.
+    #[serde(rename = "context7CompatibleLibraryID")]
+    pub library_id: String,
+    /// Maximum number of tokens of documentation to retrieve (default: 5000). Higher values provide more context but consume more tokens.
+    pub tokens: Option<NonZeroU32>,
+    /// Topic to focus documentation on (e.g., 'hooks', 'routing').
+    pub topic: Option<String>,
+}
+
+/// Most libraries `get-multiple-library-docs` fetches in one call.
+pub const MAX_BATCH_LIBRARIES: usize = 10;
+
+/// Arguments of `get-multiple-library-docs`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct GetMultipleLibraryDocsArgs {
+    /// Libraries to fetch documentation for (1 to 10), returned in the same order.
+    #[schemars(length(min = 1, max = 10))]
+    pub libraries: Vec<LibraryDocsRequest>,
+    /// Optional token budget shared by all libraries. Libraries with their own 'tokens' keep them; the rest split what is left by 'weight', each getting at least 1000 tokens.
+    #[serde(rename = "totalTokens")]
+    pub total_tokens: Option<NonZeroU32>,
+}
+
+impl GetMultipleLibraryDocsArgs {
+    /// Checks the limits the schema advertises but deserialization cannot enforce.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.libraries.len() {
+            0 => Err("libraries must list at least one library".to_string()),
+            n if n > MAX_BATCH_LIBRARIES => Err(format!(
+                "libraries lists {n} libraries; at most {MAX_BATCH_LIBRARIES} can be fetched at once"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The tokens to fetch for each library, dividing `totalTokens` when it is given.
+    pub fn library_tokens(&self) -> Result<Vec<Option<u32>>, String> {
+        let requested = self
+            .libraries
+            .iter()
+            .map(|library| library.tokens.map(NonZeroU32::get));
+        let Some(total) = self.total_tokens else {
+            return Ok(requested.collect());
+        };
+        let shares: Vec<TokenShare> = requested
+            .zip(&self.libraries)
+            .map(|(tokens, library)| {
+                tokens.map_or_else(
+                    || TokenShare::Weighted(library.weight.map_or(1, NonZeroU32::get)),
+                    TokenShare::Fixed,
+                )
+            })
+            .collect();
+        let tokens = split_token_budget(total.get(), &shares)?;
+        Ok(tokens.into_iter().map(Some).collect())
+    }
+}
+
+/// One library requested from `get-multiple-library-docs`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDocsRequest {
+    /// Exact Context7-compatible library ID (e.g., '/vercel/next.js' or '/vercel/next.js/v14.3.0-canary.87').
+    pub library_id: String,
+    /// Maximum number of tokens of documentation to retrieve for this library (default: 5000).
+    pub tokens: Option<NonZeroU32>,
+    /// Topic to focus this library's documentation on (e.g., 'hooks', 'routing').
+    pub topic: Option<String>,
+    /// Relative share of 'totalTokens' for this library (default: 1); give the most relevant libraries a higher weight.
+    pub weight: Option<NonZeroU32>,
+}
+
+/// Arguments of `list-project-libraries`, which takes none.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct ListProjectLibrariesArgs {}
+
+/// Deserializes the arguments of a call to `tool`, describing what is wrong on failure.
+pub fn parse_arguments<T: DeserializeOwned>(
+    tool: &str,
+    arguments: Option<JsonObject>,
+) -> Result<T, String> {
+    serde_path_to_error::deserialize(serde_json::Value::Object(arguments.unwrap_or_default()))
+        .map_err(|e| format!("Invalid arguments for tool {tool}: {e}"))
+}
+
+/// Structured result of `resolve-library-id`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveLibraryOutput {
+    /// Matching libraries, best first.
+    pub results: Vec<SearchResult>,
+    /// The library to use, or `null` when nothing matched.
+    pub best_match: Option<BestMatch>,
+}
+
+/// The library `resolve-library-id` selected for the query.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BestMatch {
+    /// Context7-compatible library ID to pass to `get-library-docs`,
+    /// including the selected version when one was requested.
+    pub library_id: String,
+    pub title: String,
+    /// How the requested version was matched, when a version was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionMatch>,
+}
+
+impl BestMatch {
+    /// Picks `result`, pinned to the published version that best fits `requested`.
+    #[must_use]
+    pub fn new(result: &SearchResult, requested: Option<&str>) -> Self {
+        let version = requested.and_then(|requested| {
+            select_version(result.versions.as_deref().unwrap_or_default(), requested)
+        });
+        let library_id = version.as_ref().map_or_else(
+            || result.id.clone(),
+            |version| format!("{}/{}", result.id.trim_end_matches('/'), version.version()),
+        );
+        Self {
+            library_id,
+            title: result.title.clone(),
+            version,
+        }
+    }
+}
+
+impl ResolveLibraryOutput {
+    /// Wraps search results, selecting the first one as the best match.
+    #[must_use]
+    pub fn new(response: &SearchResponse, version: Option<&str>) -> Self {
+        Self {
+            results: response.results.clone(),
+            best_match: response
+                .results
+                .first()
+                .map(|best| BestMatch::new(best, version)),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BestMatch, GetLibraryDocsArgs, GetMultipleLibraryDocsArgs, ResolveLibraryArgs,
+        ResolveLibraryOutput, SearchResponse, SearchResult, VersionMatch, parse_arguments,
+    };
+    use serde_json::json;
+
+    fn next_js() -> SearchResult {
+        SearchResult {
+            id: "/vercel/next.js".to_string(),
+            title: "Next.js".to_string(),
+            description: "The React Framework".to_string(),
+            total_snippets: Some(3000),
+            trust_score: Some(9.0),
+            versions: Some(vec!["v14.2.0".to_string(), "v13.5.6".to_string()]),
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn test_best_match_pins_requested_version() {
+        let best = BestMatch::new(&next_js(), Some("13"));
+        assert_eq!(best.library_id, "/vercel/next.js/v13.5.6");
+        assert_eq!(
+            best.version,
+            Some(VersionMatch::InRange("v13.5.6".to_string()))
+        );
+
+        let latest = BestMatch::new(&next_js(), None);
+        assert_eq!(latest.library_id, "/vercel/next.js");
+        assert_eq!(latest.version, None);
+    }
+
+    #[test]
+    fn test_resolve_library_output_serialization() {
+        let response = SearchResponse {
+            results: vec![next_js()],
+            error: None,
+        };
+        let output =
+            serde_json::to_value(ResolveLibraryOutput::new(&response, Some("12"))).unwrap();
+        assert_eq!(output["results"][0]["totalSnippets"], 3000);
+        assert_eq!(
+            output["bestMatch"],
+            json!({
+                "libraryId": "/vercel/next.js/v13.5.6",
+                "title": "Next.js",
+                "version": { "kind": "nearest", "version": "v13.5.6" }
+            })
+        );
+
+        let empty = SearchResponse {
+            results: Vec::new(),
+            error: None,
+        };
+        let output = serde_json::to_value(ResolveLibraryOutput::new(&empty, None)).unwrap();
+        assert_eq!(output, json!({ "results": [], "bestMatch": null }));
+    }
+
+    #[test]
+    fn test_parse_arguments_deserializes_tool_arguments() {
+        let args: GetLibraryDocsArgs = parse_arguments(
+            "get-library-docs",
+            json!({ "context7CompatibleLibraryID": "/vercel/next.js", "tokens": 3000 })
+                .as_object()
+                .cloned(),
+        )
+        .unwrap();
+        assert_eq!(args.library_id, "/vercel/next.js");
+        assert_eq!(args.tokens.map(|t| t.get()), Some(3000));
+        assert_eq!(args.topic, None);
+
+        let args: ResolveLibraryArgs = parse_arguments(
+            "resolve-library-id",
+            json!({ "libraryName": "next.js", "version": "  " })
+                .as_object()
+                .cloned(),
+        )
+        .unwrap();
+        assert_eq!(args.version(), None);
+    }
+
+    #[test]
+    fn test_parse_arguments_rejects_invalid_values() {
+        let error = parse_arguments::<ResolveLibraryArgs>("resolve-library-id", None).unwrap_err();
+        assert!(error.starts_with("Invalid arguments for tool resolve-library-id:"));
+        assert!(error.contains("missing field `libraryName`"));
+
+        for tokens in [json!(0), json!(-5), json!(2.5), json!("5000")] {
+            let error = parse_arguments::<GetLibraryDocsArgs>(
+                "get-library-docs",
+                json!({ "context7CompatibleLibraryID": "/vercel/next.js", "tokens": tokens })
+                    .as_object()
+                    .cloned(),
+            )
+            .unwrap_err();
+            assert!(
+                error.contains("tokens") || error.contains("invalid"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_arguments_are_limited() {
+        let parse = |count: usize| {
+            let libraries: Vec<_> = (0..count)
+                .map(|i| json!({ "libraryId": format!("/org/lib{i}"), "topic": "setup" }))
+                .collect();
+            parse_arguments::<GetMultipleLibraryDocsArgs>(
+                "get-multiple-library-docs",
+                json!({ "libraries": libraries }).as_object().cloned(),
+            )
+            .unwrap()
+        };
+
+        let args = parse(3);
+        assert!(args.validate().is_ok());
+        assert_eq!(args.libraries[2].library_id, "/org/lib2");
+        assert_eq!(args.libraries[2].topic.as_deref(), Some("setup"));
+
+        assert!(parse(0).validate().unwrap_err().contains("at least one"));
+        assert!(parse(11).validate().unwrap_err().contains("at most 10"));
+    }
+
+    #[test]
+    fn test_batch_tokens_split_total_budget() {
+        let parse = |arguments: serde_json::Value| {
+            parse_arguments::<GetMultipleLibraryDocsArgs>(
+                "get-multiple-library-docs",
+                arguments.as_object().cloned(),
+            )
+            .unwrap()
+        };
+        let libraries = json!([
+            { "libraryId": "/vercel/next.js", "weight": 3 },
+            { "libraryId": "/prisma/prisma" },
+            { "libraryId": "/vitest-dev/vitest", "tokens": 1500 }
+        ]);
+
+        let args = parse(json!({ "libraries": libraries }));
+        assert_eq!(args.library_tokens().unwrap(), [None, None, Some(1500)]);
+
+        let args = parse(json!({ "libraries": libraries, "totalTokens": 9500 }));
+        assert_eq!(
+            args.library_tokens().unwrap(),
+            [Some(5500), Some(2500), Some(1500)]
+        );
+
+        let args = parse(json!({ "libraries": libraries, "totalTokens": 2000 }));
+        assert!(args.library_tokens().is_err());
+    }
+}
@@ -0,0 +1,382 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// How a requested version was satisfied by the versions a library publishes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "kind", content = "version", rename_all = "camelCase")]
+pub enum VersionMatch {
+    /// The requested version is available verbatim.
+    Exact(String),
+    /// The highest available version satisfying the requested range.
+    InRange(String),
+    /// Nothing satisfies the request; this is the closest available version.
+    Nearest(String),
+}
+
+impl VersionMatch {
+    /// The selected version as the library publishes it.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        match self {
+            Self::Exact(v) | Self::InRange(v) | Self::Nearest(v) => v,
+        }
+    }
+}
+
+/// Picks the version in `available` that best satisfies `requested`.
+///
+/// `requested` may be a version (`14.3.0`, `v2`) or a range in Cargo or npm
+/// syntax (`^18.2`, `~1.4`, `>= 1.0, < 2`, `1.2 - 1.4`, `1.x || 2.x`). Available versions may
+/// carry prefixes such as `v` or `tokio-`. Returns `None` when no available
+/// version can be parsed.
+#[must_use]
+pub fn select_version(available: &[String], requested: &str) -> Option<VersionMatch> {
+    let requested = requested.trim();
+    let is_plain_version = requested
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+    if is_plain_version
+        && let Some(exact) = available
+            .iter()
+            .find(|v| strip_prefix(v) == strip_prefix(requested))
+    {
+        return Some(VersionMatch::Exact(exact.clone()));
+    }
+
+    let parsed: Vec<(Version, &String)> = available
+        .iter()
+        .filter_map(|v| Some((Version::parse(v)?, v)))
+        .collect();
+    if parsed.is_empty() {
+        return None;
+    }
+
+    let req = VersionReq::parse(requested)?;
+    let matching = |allow_pre: bool| {
+        parsed
+            .iter()
+            .filter(|(v, _)| (allow_pre || v.pre.is_empty()) && req.matches(v))
+            .max_by(|a, b| a.0.cmp(&b.0))
+    };
+    if let Some((_, version)) = matching(req.allows_pre()).or_else(|| matching(true)) {
+        return Some(VersionMatch::InRange((*version).clone()));
+    }
+
+    // The closest candidates are the newest version below the target and the
+    // oldest one above it; pick whichever differs in a less significant component.
+    let target = req.target();
+    let below = parsed
+        .iter()
+        .filter(|(v, _)| *v < target)
+        .max_by(|a, b| a.0.cmp(&b.0));
+    let above = parsed
+        .iter()
+        .filter(|(v, _)| *v >= target)
+        .min_by(|a, b| a.0.cmp(&b.0));
+    let nearest = match (below, above) {
+        (Some(below), Some(above)) => {
+            if below.0.distance(&target) < above.0.distance(&target) {
+                below
+            } else {
+                above
+            }
+        }
+        (Some(only), None) | (None, Some(only)) => only,
+        (None, None) => return None,
+    };
+    Some(VersionMatch::Nearest(nearest.1.clone()))
+}
+
+/// Drops everything before the first digit, e.g. `v1.2` or `tokio-1.2` to `1.2`.
+fn strip_prefix(version: &str) -> &str {
+    version
+        .find(|c: char| c.is_ascii_digit())
+        .map_or(version, |start| &version[start..])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<String>,
+}
+
+impl Version {
+    fn parse(text: &str) -> Option<Self> {
+        let partial = Partial::parse(text)?;
+        Some(partial.floor())
+    }
+
+    /// How far apart two versions are, most significant component first.
+    const fn distance(&self, other: &Self) -> (u64, u64, u64) {
+        (
+            self.major.abs_diff(other.major),
+            self.minor.abs_diff(other.minor),
+            self.patch.abs_diff(other.patch),
+        )
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_pre(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Semver precedence: numeric identifiers compare numerically and sort first.
+fn compare_pre(a: &[String], b: &[String]) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// A possibly incomplete version such as `1`, `1.2` or `1.x`.
+#[derive(Debug, Clone)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<String>,
+}
+
+impl Partial {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = if text.starts_with(['*', 'x', 'X']) {
+            text
+        } else {
+            strip_prefix(text)
+        };
+        let text = text.split('+').next()?;
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (text, Vec::new()),
+        };
+
+        let mut parts = core.split('.');
+        let mut component = || -> Option<Option<u64>> {
+            match parts.next() {
+                None | Some("*" | "x" | "X") => Some(None),
+                Some(part) => part.parse().ok().map(Some),
+            }
+        };
+        let major = component()?;
+        let minor = component()?;
+        let patch = component()?;
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+
+    /// The lowest version this partial describes.
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+        }
+    }
+
+    const fn is_complete(&self) -> bool {
+        self.major.is_some() && self.minor.is_some() && self.patch.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    /// A bare version: exact when complete, a wildcard when partial.
+    Bare,
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+    op: Op,
+    version: Partial,
+}
+
+impl Comparator {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| text.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Bare, text));
+        Some(Self {
+            op,
+            version: Partial::parse(rest)?,
+        })
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let floor = self.version.floor();
+        match self.op {
+            Op::Greater => version > &floor && !self.within_partial(version),
+            Op::GreaterEq => version >= &floor,
+            Op::Less => version < &floor,
+            Op::LessEq => version <= &floor || self.within_partial(version),
+            Op::Exact | Op::Bare if self.version.is_complete() => {
+                version.cmp(&floor) == Ordering::Equal
+            }
+            Op::Exact | Op::Bare => self.within_partial(version),
+            Op::Tilde => {
+                version >= &floor
+                    && match self.version.minor {
+                        Some(minor) => version.major == floor.major && version.minor == minor,
+                        None => version.major == floor.major,
+                    }
+            }
+            Op::Caret => version >= &floor && self.caret_upper_matches(version, &floor),
+        }
+    }
+
+    /// Whether `version` falls inside the wildcard positions of a partial version.
+    fn within_partial(&self, version: &Version) -> bool {
+        self.version
+            .major
+            .is_none_or(|major| version.major == major)
+            && self
+                .version
+                .minor
+                .is_none_or(|minor| version.minor == minor)
+            && self
+                .version
+                .patch
+                .is_none_or(|patch| version.patch == patch)
+            && (self.version.patch.is_none() || version.pre == self.version.pre)
+    }
+
+    /// `^` allows changes that do not modify the left-most non-zero component.
+    fn caret_upper_matches(&self, version: &Version, floor: &Version) -> bool {
+        if floor.major > 0 || self.version.minor.is_none() {
+            version.major == floor.major
+        } else if floor.minor > 0 || self.version.patch.is_none() {
+            version.major == 0 && version.minor == floor.minor
+        } else {
+            version.major == 0 && version.minor == 0 && version.patch == floor.patch
+        }
+    }
+}
+
+/// A version requirement: alternatives (`||`) of comparator sets.
+#[derive(Debug, Clone)]
+struct VersionReq {
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    fn parse(text: &str) -> Option<Self> {
+        let alternatives = text
+            .split("||")
+            .map(|set| {
+                let set = set.trim();
+                if set.is_empty() || set == "*" {
+                    return Some(Vec::new());
+                }
+                Self::parse_set(set)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { alternatives })
+    }
+
+    /// Parses comparators separated by commas or spaces, including hyphen ranges.
+    fn parse_set(set: &str) -> Option<Vec<Comparator>> {
+        let mut tokens = set
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .peekable();
+        let mut comparators = Vec::new();
+        while let Some(token) = tokens.next() {
+            // `>= 1.0`: the operator may stand apart from its version.
+            let token = if token.chars().all(|c| "<>=^~".contains(c)) {
+                format!("{token}{}", tokens.next()?)
+            } else {
+                token.to_string()
+            };
+
+            // `1.2 - 1.4` includes every version of 1.4, as in npm.
+            if tokens.next_if_eq(&"-").is_some() {
+                comparators.push(Comparator {
+                    op: Op::GreaterEq,
+                    version: Partial::parse(&token)?,
+                });
+                comparators.push(Comparator {
+                    op: Op::LessEq,
+                    version: Partial::parse(tokens.next()?)?,
+                });
+            } else {
+                comparators.push(Comparator::parse(&token)?);
+            }
+        }
+        Some(comparators)
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        self.alternatives
+            .iter()
+            .any(|set| set.iter().all(|comparator| comparator.matches(version)))
+    }
+
+    /// Pre-releases only match when the request names one.
+    fn allows_pre(&self) -> bool {
+        self.alternatives
+            .iter()
+            .flatten()
+            .any(|comparator| !comparator.version.pre.is_empty())
+    }
+
+    /// The version the request is centred on, for finding the nearest one.
+    fn target(&self) -> Version {
+        self.alternatives.iter().flatten().next().map_or_else(
+            || Version {
+                major: 0,
+                minor: 0,
+                patch: 0,
+                pre: Vec::new(),
+            },
+            |comparator| comparator.version.floor(),
+        )
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use crate::{VersionMatch, select_version};
+
+    fn versions(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_select_version_prefers_exact_match() {
+        let available = versions(&["v14.3.0-canary.87", "v14.2.0", "v13.5.6"]);
+        assert_eq!(
+            select_version(&available, "14.2.0"),
+            Some(VersionMatch::Exact("v14.2.0".to_string()))
+        );
+        assert_eq!(
+            select_version(&available, "v14.3.0-canary.87"),
+            Some(VersionMatch::Exact("v14.3.0-canary.87".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_picks_highest_in_range() {
+        let available = versions(&["18.3.1", "18.2.0", "17.0.2", "19.0.0-rc.1"]);
+        let in_range = |req: &str| select_version(&available, req);
+
+        assert_eq!(in_range("18"), Some(VersionMatch::InRange("18.3.1".into())));
+        assert_eq!(
+            in_range("^18.2"),
+            Some(VersionMatch::InRange("18.3.1".into()))
+        );
+        assert_eq!(
+            in_range("~18.2.0"),
+            Some(VersionMatch::InRange("18.2.0".into()))
+        );
+        assert_eq!(
+            in_range(">=17, <18"),
+            Some(VersionMatch::InRange("17.0.2".into()))
+        );
+        assert_eq!(
+            in_range("17.x || 16.x"),
+            Some(VersionMatch::InRange("17.0.2".into()))
+        );
+        assert_eq!(in_range("*"), Some(VersionMatch::InRange("18.3.1".into())));
+        // Pre-releases are only chosen when nothing stable matches.
+        assert_eq!(
+            in_range(">=18"),
+            Some(VersionMatch::InRange("18.3.1".into()))
+        );
+        assert_eq!(
+            in_range("19"),
+            Some(VersionMatch::InRange("19.0.0-rc.1".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_allows_space_after_operator() {
+        let available = versions(&["2.1.0", "1.9.3", "1.0.0", "0.9.0"]);
+        assert_eq!(
+            select_version(&available, ">= 1.0, < 2"),
+            Some(VersionMatch::InRange("1.9.3".into()))
+        );
+        assert_eq!(
+            select_version(&available, "< 1"),
+            Some(VersionMatch::InRange("0.9.0".into()))
+        );
+        assert_eq!(
+            select_version(&available, "^ 1.0 || ~ 0.9"),
+            Some(VersionMatch::InRange("1.9.3".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_hyphen_ranges() {
+        let available = versions(&["1.5.0", "1.4.7", "1.3.0", "1.1.0"]);
+        assert_eq!(
+            select_version(&available, "1.2 - 1.4"),
+            Some(VersionMatch::InRange("1.4.7".into()))
+        );
+        assert_eq!(
+            select_version(&available, "1.2.0 - 1.4.0"),
+            Some(VersionMatch::InRange("1.3.0".into()))
+        );
+        assert_eq!(
+            select_version(&available, "1.0 - 1.1 || 1.5 - 2"),
+            Some(VersionMatch::InRange("1.5.0".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_wildcards() {
+        let available = versions(&["2.0.0", "1.4.2", "1.4.0", "1.2.0"]);
+        assert_eq!(
+            select_version(&available, "1.x"),
+            Some(VersionMatch::InRange("1.4.2".into()))
+        );
+        assert_eq!(
+            select_version(&available, "1.2.X"),
+            Some(VersionMatch::InRange("1.2.0".into()))
+        );
+        assert_eq!(
+            select_version(&available, "1.*"),
+            Some(VersionMatch::InRange("1.4.2".into()))
+        );
+        assert_eq!(
+            select_version(&available, "x"),
+            Some(VersionMatch::InRange("2.0.0".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_caret_on_zero_major() {
+        let available = versions(&["0.3.0", "0.2.9", "0.2.1"]);
+        assert_eq!(
+            select_version(&available, "^0.2.1"),
+            Some(VersionMatch::InRange("0.2.9".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_falls_back_to_nearest() {
+        let available = versions(&["tokio-1.47.1", "tokio-1.38.0", "tokio-0.2.25"]);
+        assert_eq!(
+            select_version(&available, "1.40"),
+            Some(VersionMatch::Nearest("tokio-1.38.0".into()))
+        );
+        assert_eq!(
+            select_version(&available, "^2"),
+            Some(VersionMatch::Nearest("tokio-1.47.1".into()))
+        );
+    }
+
+    #[test]
+    fn test_select_version_without_parseable_versions() {
+        assert_eq!(select_version(&versions(&["main", "next"]), "1.0"), None);
+        assert_eq!(select_version(&versions(&["1.0.0"]), "latest"), None);
+    }
+}